@@ -43,6 +43,9 @@ fn cursor_action(
                 update_map.update();
                 planet.demolition(coords);
             }
+            CursorMode::SetPriority(priority) => {
+                planet.set_structure_priority(coords, priority);
+            }
             CursorMode::EditBiome(biome) => {
                 update_map.update();
                 planet.edit_biome(coords, biome);
@@ -69,6 +72,7 @@ fn new_structure(kind: StructureKind) -> Structure {
         StructureKind::OxygenGenerator => Structure::OxygenGenerator,
         StructureKind::FertilizationPlant => Structure::FertilizationPlant,
         StructureKind::Heater => Structure::Heater,
+        StructureKind::Warehouse => Structure::Warehouse,
         _ => unreachable!(),
     }
 }
@@ -24,6 +24,10 @@ impl Plugin for AssetsPlugin {
             .add_plugin(RonAssetPlugin::<StructureAssetList>::new(&[
                 "structures.ron",
             ]))
+            .add_plugin(RonAssetPlugin::<NaturalEventParamsAsset>::new(&[
+                "natural_events.ron",
+            ]))
+            .add_plugin(RonAssetPlugin::<EventCardList>::new(&["events.ron"]))
             .add_loading_state(
                 LoadingState::new(GameState::AssetLoading)
                     .continue_to_state(GameState::MainMenu)
@@ -102,6 +106,16 @@ pub struct BiomeAssetList(FnvHashMap<Biome, BiomeAttrs>);
 #[uuid = "801a2daa-956d-469a-8e83-3610fbca21fd"]
 pub struct StructureAssetList(FnvHashMap<StructureKind, StructureAttrs>);
 
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "3c1c4f5e-6b0a-4d8e-9a57-2f0d6e2b7c41"]
+pub struct NaturalEventParamsAsset(NaturalEventParams);
+
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[serde(transparent)]
+#[uuid = "a8f2d3b6-1e47-4c09-8d5a-6b9e0c7f2a13"]
+pub struct EventCardList(Vec<EventCard>);
+
 #[derive(Resource)]
 pub struct TextureAtlasMaps {
     pub biomes: FnvHashMap<Biome, Handle<TextureAtlas>>,
@@ -116,6 +130,15 @@ pub struct ParamsAssetCollection {
     biomes: Handle<BiomeAssetList>,
     #[asset(path = "structures/list.structures.ron")]
     structures: Handle<StructureAssetList>,
+    #[asset(path = "planet.natural_events.ron")]
+    natural_events: Handle<NaturalEventParamsAsset>,
+    // Loading folders is not supported on wasm
+    #[cfg_attr(not(target_arch = "wasm32"), asset(path = "events", collection(typed)))]
+    #[cfg_attr(
+        target_arch = "wasm32",
+        asset(paths("events/basic.events.ron"), collection(typed))
+    )]
+    event_cards: Vec<Handle<EventCardList>>,
 }
 
 define_asset_list_from_enum! {
@@ -147,11 +170,13 @@ fn create_assets_list(
     params_asset_collection: Res<ParamsAssetCollection>,
     biome_textures: Res<BiomeTextures>,
     structure_textures: Res<StructureTextures>,
-    (params_asset, biome_asset_list, structure_asset_list): (
+    (params_asset, biome_asset_list, structure_asset_list, natural_event_params_asset): (
         Res<Assets<ParamsAsset>>,
         Res<Assets<BiomeAssetList>>,
         Res<Assets<StructureAssetList>>,
+        Res<Assets<NaturalEventParamsAsset>>,
     ),
+    event_card_lists: Res<Assets<EventCardList>>,
     mut texture_atlas_assets: ResMut<Assets<TextureAtlas>>,
 ) {
     let biome_asset_list = biome_asset_list
@@ -194,6 +219,18 @@ fn create_assets_list(
         .0;
     params.biomes = biome_asset_list.0.clone();
     params.structures = structure_asset_list.0.clone();
+    params.natural_events = natural_event_params_asset
+        .get(&params_asset_collection.natural_events)
+        .unwrap()
+        .0
+        .clone();
+    params.event_cards = params_asset_collection
+        .event_cards
+        .iter()
+        .flat_map(|handle| event_card_lists.get(handle).unwrap().0.clone())
+        .collect();
+    // Folders are loaded in arbitrary order
+    params.event_cards.sort_by(|a, b| a.id.cmp(&b.id));
 
     command.insert_resource(params);
     command.insert_resource(TextureAtlasMaps { biomes, structures });
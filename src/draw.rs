@@ -33,7 +33,8 @@ impl Plugin for DrawPlugin {
                     .label("draw")
                     .with_system(update_layered_tex_map.pipe(spawn_map_textures))
                     .with_system(spawn_structure_textures)
-                    .with_system(spawn_overlay_meshes),
+                    .with_system(spawn_overlay_meshes)
+                    .with_system(spawn_night_meshes),
             )
             .add_system(reset_update_map.after("draw"));
     }
@@ -58,9 +59,19 @@ fn update_layered_tex_map(
     let (w, h) = planet.map.size();
     let mut tiles = Array2d::new(w, h, ArrayVec::new());
 
+    // Tiles fully covered by ice are drawn as ice sheet
+    let drawn_biome = |p: Coords| {
+        let tile = &planet.map[p];
+        if tile.ice_cover(&params) >= 1.0 {
+            Biome::IceSheet
+        } else {
+            tile.biome
+        }
+    };
+
     for &i in params.biomes.keys() {
         for pos in RectIter::new((0, 0), (w - 1, h - 1)) {
-            let biome_i = planet.map[pos].biome;
+            let biome_i = drawn_biome(pos);
             if biome_i != i {
                 continue;
             }
@@ -70,7 +81,7 @@ fn update_layered_tex_map(
             for d in Direction::EIGHT_DIRS {
                 let p = pos + d.as_coords();
                 if tiles.in_range(p) {
-                    let surround_tile_i = planet.map[p].biome;
+                    let surround_tile_i = drawn_biome(p);
                     let z = params.biomes[&surround_tile_i].z;
                     if z < tile_z && !tiles[pos].contains(&surround_tile_i) {
                         tiles[pos].push(surround_tile_i);
@@ -206,6 +217,7 @@ fn spawn_overlay_meshes(
     color_materials: Res<ColorMaterials>,
     in_screen_tile_range: Res<InScreenTileRange>,
     planet: Res<Planet>,
+    sim: Res<Sim>,
     current_layer: Res<OverlayLayerKind>,
     mut prev_layer: Local<OverlayLayerKind>,
     mut tile_mesh: Local<Option<Handle<Mesh>>>,
@@ -244,7 +256,64 @@ fn spawn_overlay_meshes(
             .spawn(MaterialMesh2dBundle {
                 mesh: tile_mesh.clone().into(),
                 transform: Transform::from_xyz(x, y, 800.0),
-                material: color_materials.get(&planet, p, *current_layer),
+                material: color_materials.get(&planet, &sim, p, *current_layer),
+                ..default()
+            })
+            .id();
+        mesh_entities.push(id);
+    }
+}
+
+fn spawn_night_meshes(
+    mut commands: Commands,
+    update_map: Res<UpdateMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    color_materials: Res<ColorMaterials>,
+    in_screen_tile_range: Res<InScreenTileRange>,
+    planet: Res<Planet>,
+    params: Res<Params>,
+    mut tile_mesh: Local<Option<Handle<Mesh>>>,
+    mut mesh_entities: Local<Vec<Entity>>,
+) {
+    if !update_map.need_update {
+        return;
+    }
+
+    for entity in mesh_entities.iter() {
+        commands.entity(*entity).despawn();
+    }
+    mesh_entities.clear();
+
+    // Shade only if the terminator is visible on the map
+    let sun_position = planet.sun_position(&params);
+    if sun_position.sub_solar_longitude.is_none() {
+        return;
+    }
+
+    let tile_mesh = if let Some(tile_mesh) = tile_mesh.clone() {
+        tile_mesh
+    } else {
+        *tile_mesh = Some(meshes.add(Mesh::from(shape::Quad::new(Vec2::new(
+            TILE_SIZE, TILE_SIZE,
+        )))));
+        tile_mesh.clone().unwrap()
+    };
+
+    for p_screen in RectIter::new(in_screen_tile_range.from, in_screen_tile_range.to) {
+        let p = coord_rotation_x(planet.map.size(), p_screen);
+        let (longitude, latitude) = planet.calc_longitude_latitude(p);
+        if sun_position.insolation(longitude, latitude) > 0.0 {
+            continue;
+        }
+
+        let x = p_screen.0 as f32 * TILE_SIZE + TILE_SIZE / 2.0;
+        let y = p_screen.1 as f32 * TILE_SIZE + TILE_SIZE / 2.0;
+
+        let id = commands
+            .spawn(MaterialMesh2dBundle {
+                mesh: tile_mesh.clone().into(),
+                transform: Transform::from_xyz(x, y, 700.0),
+                material: color_materials.night.clone(),
                 ..default()
             })
             .id();
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use geom::Coords;
 use strum::{AsRefStr, EnumIter};
 
-use crate::planet::Planet;
+use crate::planet::{Planet, Sim};
 
 #[derive(Clone, Copy, Debug)]
 pub struct OverlayPlugin;
@@ -19,6 +19,8 @@ pub enum OverlayLayerKind {
     #[default]
     None,
     AirTemprature,
+    SurfaceTemprature,
+    Habitability,
 }
 
 pub const N_POINTS: usize = 64;
@@ -26,16 +28,27 @@ pub const N_POINTS: usize = 64;
 #[derive(Resource)]
 pub struct ColorMaterials {
     pub white_yellow_red: Vec<Handle<ColorMaterial>>,
+    pub red_green: Vec<Handle<ColorMaterial>>,
+    pub night: Handle<ColorMaterial>,
 }
 
 impl ColorMaterials {
     pub fn get(
         &self,
         planet: &Planet,
+        sim: &Sim,
         p: Coords,
-        _kind: OverlayLayerKind,
+        kind: OverlayLayerKind,
     ) -> Handle<ColorMaterial> {
-        let temp = planet.map[p].temp;
+        if kind == OverlayLayerKind::Habitability {
+            let i = ((sim.habitability[p] * N_POINTS as f32) as usize).clamp(0, N_POINTS - 1);
+            return self.red_green[i].clone();
+        }
+
+        let temp = match kind {
+            OverlayLayerKind::SurfaceTemprature => planet.map[p].surface_temp,
+            _ => planet.map[p].temp,
+        };
 
         let i = if temp < 263.15 {
             0
@@ -73,6 +86,25 @@ fn prepare_color_materials(mut commands: Commands, mut materials: ResMut<Assets<
         })
         .collect::<Vec<_>>();
 
-    let color_materials = ColorMaterials { white_yellow_red };
+    let red_green = (0..N_POINTS)
+        .map(|i| {
+            let a = i as f32 / (N_POINTS - 1) as f32;
+            materials.add(ColorMaterial {
+                color: Color::rgba(1.0 - a, a, 0.0, 0.4),
+                texture: None,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let night = materials.add(ColorMaterial {
+        color: Color::rgba(0.0, 0.0, 0.1, 0.5),
+        texture: None,
+    });
+
+    let color_materials = ColorMaterials {
+        white_yellow_red,
+        red_green,
+        night,
+    };
     commands.insert_resource(color_materials);
 }
@@ -32,6 +32,7 @@ impl Planet {
 
         let kind = structure.kind();
         self.map[p].structure = structure;
+        self.map[p].structure_priority = None;
 
         for p_rel in size.occupied_tiles().into_iter() {
             self.map[p + p_rel].structure = Structure::Occupied { by: p };
@@ -43,6 +44,29 @@ impl Planet {
 
     pub fn demolition(&mut self, p: Coords) {
         self.map[p].structure = Structure::None;
+        self.map[p].structure_priority = None;
+    }
+
+    /// Set the priority of the structure on the tile. None follows the priority of its kind
+    pub fn set_structure_priority(&mut self, p: Coords, priority: Option<Priority>) {
+        let p = if let Structure::Occupied { by } = self.map[p].structure {
+            by
+        } else {
+            p
+        };
+        self.map[p].structure_priority = priority;
+    }
+
+    /// Priority of the structure at p
+    pub fn structure_priority(&self, p: Coords) -> Priority {
+        let tile = &self.map[p];
+        tile.structure_priority.unwrap_or_else(|| {
+            self.player
+                .structure_priority
+                .get(&tile.structure.kind())
+                .copied()
+                .unwrap_or_default()
+        })
     }
 
     pub fn build_orbital_building(&mut self, kind: OrbitalBuildingKind, params: &Params) {
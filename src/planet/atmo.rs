@@ -7,6 +7,8 @@ pub struct Atmosphere {
     pub atm: f32,
     /// Gases mass [Mt]
     pub mass: FnvHashMap<GasKind, f32>,
+    /// Optical depth of dust
+    pub dust: f32,
 }
 
 impl Atmosphere {
@@ -14,14 +16,67 @@ impl Atmosphere {
         Atmosphere {
             atm: 0.0,
             mass: start_params.atmo_mass.clone(),
+            dust: 0.0,
         }
     }
 
     pub fn total_mass(&self) -> f32 {
         self.mass.values().sum()
     }
+
+    /// Partial pressure of the given gas [atm]
+    pub fn partial_pressure(&self, kind: GasKind, params: &Params) -> f32 {
+        self.mass[&kind] / params.sim.total_mass_per_atm
+    }
+}
+
+/// Gravitational constant [m^3/(kg*s^2)]
+pub const GRAVITATIONAL_CONSTANT: f32 = 6.674E-11;
+/// Boltzmann constant [J/K]
+pub const BOLTZMANN_CONSTANT: f32 = 1.380649E-23;
+/// Atomic mass unit [kg]
+pub const ATOMIC_MASS_UNIT: f32 = 1.660539E-27;
+
+impl PlanetBasics {
+    /// Surface gravity [m/s^2]
+    pub fn surface_gravity(&self) -> f32 {
+        4.0 / 3.0 * PI * GRAVITATIONAL_CONSTANT * self.density * self.radius
+    }
+
+    /// Escape velocity [m/s]
+    pub fn escape_velocity(&self) -> f32 {
+        (2.0 * self.surface_gravity() * self.radius).sqrt()
+    }
+}
+
+impl GasKind {
+    /// Molar mass [g/mol]
+    pub fn molar_mass(&self) -> f32 {
+        match self {
+            GasKind::Oxygen => 32.0,
+            GasKind::Nitrogen => 28.0,
+            GasKind::CarbonDioxide => 44.0,
+            GasKind::WaterVapor => 18.0,
+        }
+    }
 }
 
 pub fn sim_atmosphere(planet: &mut Planet, params: &Params) {
+    // Jeans escape of gases at the exosphere
+    let exosphere_temp = planet.mean_temp() * params.sim.exosphere_temp_factor;
+    let escape_velocity = planet.basics.escape_velocity();
+
+    for (kind, mass) in planet.atmo.mass.iter_mut() {
+        let thermal_velocity_sq =
+            2.0 * BOLTZMANN_CONSTANT * exosphere_temp / (kind.molar_mass() * ATOMIC_MASS_UNIT);
+        let jeans_parameter = escape_velocity * escape_velocity / thermal_velocity_sq;
+        let ratio =
+            (params.sim.atmo_escape_factor * (1.0 + jeans_parameter) * (-jeans_parameter).exp())
+                .min(1.0);
+        *mass -= *mass * ratio;
+    }
+
+    planet.atmo.dust *= 1.0 - params.sim.dust_settling_rate;
+
     planet.atmo.atm = planet.atmo.total_mass() / params.sim.total_mass_per_atm;
 }
@@ -0,0 +1,92 @@
+use super::*;
+
+/// Mass of CO2 consumed by photosynthesis per produced biomass (CH2O)
+const CO2_PER_BIOMASS: f32 = 44.0 / 30.0;
+/// Mass of O2 released by photosynthesis per produced biomass (CH2O)
+const O2_PER_BIOMASS: f32 = 32.0 / 30.0;
+
+pub fn sim_biomass(planet: &mut Planet, sim: &mut Sim, params: &Params) {
+    let co2 = planet.atmo.partial_pressure(GasKind::CarbonDioxide, params);
+    let o2 = planet.atmo.partial_pressure(GasKind::Oxygen, params);
+    let atmo_factor = if planet.atmo.atm >= params.sim.biomass_min_atm {
+        co2 / (co2 + params.sim.biomass_co2_half_saturation)
+            * (o2 / (o2 + params.sim.biomass_o2_half_saturation))
+    } else {
+        0.0
+    };
+
+    let size = planet.map.size();
+    let mut growth_death = Array2d::new(size.0, size.1, (0.0f32, 0.0f32));
+    let mut total_growth = 0.0;
+    let mut total_death = 0.0;
+
+    for p in planet.map.iter_idx() {
+        let tile = &planet.map[p];
+        let fertility = sim.fertility[p];
+        let max_biomass = params.biomes[&tile.biome].max_biomass * (1.0 + fertility);
+
+        let insolation = sim.daily_insolation[p];
+        let factor = atmo_factor
+            * temp_factor(tile.temp, params)
+            * (insolation / (insolation + params.sim.biomass_light_half_saturation))
+            * (tile.soil_moisture
+                / (tile.soil_moisture + params.sim.biomass_water_half_saturation));
+
+        let growth = params.sim.biomass_growth_rate
+            * (1.0 + fertility)
+            * factor
+            * tile.biomass.max(params.sim.biomass_seed)
+            * (1.0 - tile.biomass / max_biomass);
+        // Biomass over the capacity dies too
+        let death =
+            params.sim.biomass_death_rate * (1.0 - factor) * tile.biomass + (-growth).max(0.0);
+        let growth = growth.max(0.0);
+
+        growth_death[p] = (growth, death);
+        total_growth += growth;
+        total_death += death;
+    }
+
+    // Photosynthesis consumes CO2 and releases O2, and decay does the reverse.
+    // Growth is limited by CO2 and decay is limited by O2 in the atmosphere [Mt]
+    let kg_to_mt = sim.tile_area * 1.0E-9;
+    let co2 = planet.atmo.mass[&GasKind::CarbonDioxide];
+    let o2 = planet.atmo.mass[&GasKind::Oxygen];
+    let growth_ratio = ratio_by_limit(total_growth * kg_to_mt * CO2_PER_BIOMASS, co2);
+    let decay_ratio = ratio_by_limit(total_death * kg_to_mt * O2_PER_BIOMASS, o2);
+
+    let net_growth = total_growth * growth_ratio;
+    let decayed = total_death * decay_ratio;
+    *planet.atmo.mass.get_mut(&GasKind::CarbonDioxide).unwrap() =
+        (co2 - (net_growth - decayed) * kg_to_mt * CO2_PER_BIOMASS).max(0.0);
+    *planet.atmo.mass.get_mut(&GasKind::Oxygen).unwrap() =
+        (o2 + (net_growth - decayed) * kg_to_mt * O2_PER_BIOMASS).max(0.0);
+
+    // Dead biomass which is not decayed for lack of O2 is buried
+    for p in planet.map.iter_idx() {
+        let (growth, death) = growth_death[p];
+        let tile = &mut planet.map[p];
+        tile.biomass = (tile.biomass + growth * growth_ratio - death).max(0.0);
+    }
+}
+
+/// The ratio of a demand that can be satisfied by the available amount
+fn ratio_by_limit(demand: f32, available: f32) -> f32 {
+    if demand > available {
+        (available / demand).max(0.0)
+    } else {
+        1.0
+    }
+}
+
+/// Growth factor by temperature, which is maximum at the optimum temperature
+fn temp_factor(temp: f32, params: &Params) -> f32 {
+    let (min, opt, max) = params.sim.biomass_growth_temp;
+    if temp <= min || temp >= max {
+        0.0
+    } else if temp < opt {
+        (temp - min) / (opt - min)
+    } else {
+        (max - temp) / (max - opt)
+    }
+}
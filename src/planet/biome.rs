@@ -0,0 +1,44 @@
+use super::*;
+
+pub fn sim_biome_transition(planet: &mut Planet, params: &Params) {
+    if planet.days % params.sim.biome_transition_interval_days.max(1) != 0 {
+        return;
+    }
+
+    let mut biomes: Vec<_> = params.biomes.iter().collect();
+    biomes.sort_by_key(|(biome, attrs)| (std::cmp::Reverse(attrs.priority), **biome));
+
+    for p in planet.map.iter_idx() {
+        let tile = &mut planet.map[p];
+        let current = &params.biomes[&tile.biome];
+
+        for (biome, attrs) in &biomes {
+            // Keep the current biome if no biome with higher priority is available
+            if attrs.priority <= current.priority && current.requirements.check(tile) {
+                break;
+            }
+            if attrs.requirements.check(tile) {
+                tile.biome = **biome;
+                break;
+            }
+        }
+    }
+}
+
+impl BiomeRequirements {
+    pub fn check(&self, tile: &Tile) -> bool {
+        fn in_range(range: Option<(f32, f32)>, value: f32) -> bool {
+            if let Some((min, max)) = range {
+                min <= value && value <= max
+            } else {
+                true
+            }
+        }
+
+        in_range(self.temp, tile.temp)
+            && in_range(self.water, tile.water)
+            && in_range(self.soil_moisture, tile.soil_moisture)
+            && in_range(self.ice, tile.ice)
+            && in_range(self.biomass, tile.biomass)
+    }
+}
@@ -1,18 +1,20 @@
+use super::geometry::tiles_in_radius;
 use super::*;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-enum BuildingKind {
+pub enum BuildingKind {
     Structure(StructureKind),
     Orbital(OrbitalBuildingKind),
     StarSystem(StarSystemBuildingKind),
 }
 
-pub fn advance(planet: &mut Planet, params: &Params) {
-    let c = CheckUpkeepProduces::new(planet, params);
+pub fn advance(planet: &mut Planet, sim: &mut Sim, params: &Params) {
+    let c = CheckUpkeepProduces::new(planet, sim, params);
     planet.res.stock = c.stock;
     planet.res.diff = c.diff;
+    sim.stopped_buildings = c.stopped_buildings;
 
-    apply_building_effect(planet, &c.stopped_buildings, params);
+    apply_building_effect(planet, sim, params);
 }
 
 #[derive(Default)]
@@ -23,96 +25,255 @@ struct CheckUpkeepProduces {
 }
 
 impl CheckUpkeepProduces {
-    fn new(planet: &Planet, params: &Params) -> Self {
-        let mut c = CheckUpkeepProduces {
-            stock: planet.res.stock.clone(),
-            ..Default::default()
-        };
+    fn new(planet: &Planet, sim: &mut Sim, params: &Params) -> Self {
+        // Buildings with higher priority take scarce resources first.
+        // The order in the same priority is fixed by the kind and the position
+        let mut orbit: Vec<_> = planet.orbit.iter().collect();
+        orbit.sort_by_key(|(kind, _)| **kind);
+        let mut star_system: Vec<_> = planet.star_system.iter().collect();
+        star_system.sort_by_key(|(kind, _)| **kind);
 
-        for (kind, b) in &planet.orbit {
-            let building = &params.orbital_buildings[kind];
-            c.check_building(BuildingKind::Orbital(*kind), b.enabled, building, planet);
+        let mut queue: Vec<(Priority, BuildingKind, u32, Option<Coords>)> = Vec::new();
+        for (kind, b) in orbit {
+            queue.push((b.priority, BuildingKind::Orbital(*kind), b.enabled, None));
         }
-
-        for (kind, b) in &planet.star_system {
-            let building = &params.star_system_buildings[kind];
-            c.check_building(BuildingKind::StarSystem(*kind), b.enabled, building, planet);
+        for (kind, b) in star_system {
+            queue.push((b.priority, BuildingKind::StarSystem(*kind), b.enabled, None));
         }
-
-        for tile in planet.map.iter() {
+        for p in planet.map.iter_idx() {
+            let tile = &planet.map[p];
             let kind = tile.structure.kind();
-            if let Some(a) = params.structures.get(&kind) {
-                c.check_building(BuildingKind::Structure(kind), 1, a.as_ref(), planet);
+            if params.structures.contains_key(&kind) {
+                queue.push((
+                    planet.structure_priority(p),
+                    BuildingKind::Structure(kind),
+                    1,
+                    Some(p),
+                ));
+            }
+        }
+        queue.sort_by_key(|(priority, ..)| std::cmp::Reverse(*priority));
+
+        let attrs = |kind: BuildingKind| match kind {
+            BuildingKind::Structure(kind) => params.structures[&kind].as_ref(),
+            BuildingKind::Orbital(kind) => &params.orbital_buildings[&kind],
+            BuildingKind::StarSystem(kind) => &params.star_system_buildings[&kind],
+        };
+
+        // Resources produced in the day are available for all buildings.
+        // Stopping a building may lose its products, so repeat until no more buildings stop
+        let mut n_working: Vec<u32> = queue.iter().map(|(_, _, n, _)| *n).collect();
+        let mut c = loop {
+            let mut c = CheckUpkeepProduces {
+                stock: planet.res.stock.clone(),
+                ..Default::default()
+            };
+
+            for ((_, kind, ..), n) in queue.iter().zip(n_working.iter_mut()) {
+                *n = c.produce(*n, attrs(*kind), planet);
+            }
+
+            let mut settled = true;
+            for ((_, kind, ..), n) in queue.iter().zip(n_working.iter_mut()) {
+                let n_upkeep = c.upkeep(*n, attrs(*kind));
+                if n_upkeep < *n {
+                    *n = n_upkeep;
+                    settled = false;
+                }
+            }
+
+            if settled {
+                break c;
+            }
+        };
+
+        for ((_, kind, n, p), n_working) in queue.into_iter().zip(n_working) {
+            *c.stopped_buildings.entry(kind).or_default() += n - n_working;
+            if let Some(p) = p {
+                sim.stopped_structures[p] = n_working == 0;
             }
         }
 
         c
     }
 
-    fn check_building(
-        &mut self,
-        kind: BuildingKind,
-        n: u32,
-        building: &BuildingAttrs,
-        planet: &Planet,
-    ) {
-        let available_by_upkeep = building
-            .upkeep
-            .iter()
-            .map(|(resource_kind, v)| self.stock[resource_kind] / v)
-            .min_by(|a, b| a.total_cmp(b));
-
-        let available_by_produce = building
+    /// Add products of buildings as far as the capacity allows.
+    /// Returns the number of working buildings
+    fn produce(&mut self, n: u32, building: &BuildingAttrs, planet: &Planet) -> u32 {
+        let n = building
             .produces
             .iter()
             .map(|(resource_kind, v)| {
                 (planet.res.cap[resource_kind] - self.stock[resource_kind]) / v
             })
-            .min_by(|a, b| a.total_cmp(b));
-
-        let n_available = match (available_by_upkeep, available_by_produce) {
-            (None, None) => n,
-            (Some(a), None) | (None, Some(a)) => a.clamp(0.0, n as f32) as u32,
-            (Some(a), Some(b)) => {
-                let a = a.min(b);
-                a.clamp(0.0, n as f32) as u32
-            }
-        };
+            .fold(n as f32, f32::min)
+            .max(0.0) as u32;
+
+        let a = n as f32;
+        for (resource_kind, v) in &building.produces {
+            *self.diff.entry(*resource_kind).or_default() += *v * a;
+            *self.stock.get_mut(resource_kind).unwrap() += *v * a;
+        }
 
-        *self.stopped_buildings.entry(kind).or_default() += n - n_available;
+        n
+    }
 
-        let a = n_available as f32;
+    /// Take upkeep of buildings as far as the stock allows.
+    /// Returns the number of working buildings
+    fn upkeep(&mut self, n: u32, building: &BuildingAttrs) -> u32 {
+        let n = building
+            .upkeep
+            .iter()
+            .map(|(resource_kind, v)| self.stock[resource_kind] / v)
+            .fold(n as f32, f32::min)
+            .max(0.0) as u32;
+
+        let a = n as f32;
         for (resource_kind, v) in &building.upkeep {
             *self.diff.entry(*resource_kind).or_default() -= *v * a;
             *self.stock.get_mut(resource_kind).unwrap() -= *v * a;
         }
 
-        for (resource_kind, v) in &building.produces {
-            *self.diff.entry(*resource_kind).or_default() += *v * a;
-            *self.stock.get_mut(resource_kind).unwrap() += *v * a;
+        n
+    }
+}
+
+fn apply_building_effect(planet: &mut Planet, sim: &mut Sim, params: &Params) {
+    for p in planet.map.iter_idx() {
+        sim.heat_supply[p] = 0.0;
+        sim.fertility[p] = 0.0;
+    }
+    for factor in sim.insolation_factor.iter_mut() {
+        *factor = 1.0;
+    }
+    sim.star_dimming = 0.0;
+    planet.res.cap = Resources::base_cap(&params.start);
+
+    let working =
+        |kind: BuildingKind, n: u32| n - sim.stopped_buildings.get(&kind).copied().unwrap_or(0);
+
+    let mut effects = Vec::new();
+    for (&kind, building) in &planet.orbit {
+        if let Some(effect) = &params.orbital_buildings[&kind].effect {
+            let n = working(BuildingKind::Orbital(kind), building.enabled);
+            effects.push((effect, n as f32));
+        }
+    }
+    for (&kind, building) in &planet.star_system {
+        if let Some(effect) = &params.star_system_buildings[&kind].effect {
+            if matches!(effect, BuildingEffect::DimStar { .. }) && !building.dim_star {
+                continue;
+            }
+            let n = working(BuildingKind::StarSystem(kind), building.enabled);
+            effects.push((effect, n as f32));
+        }
+    }
+    for (effect, n) in effects {
+        apply_global_effect(planet, sim, effect, n, params);
+    }
+
+    let size = planet.map.size();
+    for p in planet.map.iter_idx() {
+        let structure_kind = planet.map[p].structure.kind();
+        let Some(attrs) = params.structures.get(&structure_kind) else {
+            continue;
+        };
+        let Some(effect) = &attrs.building.effect else {
+            continue;
+        };
+        if sim.stopped_structures[p] {
+            continue;
+        }
+
+        match effect {
+            BuildingEffect::Heater { heat } => {
+                // Heat is shared by tiles in the range
+                let area = attrs.effect_area(size, p);
+                let sum: f32 = area.iter().map(|(_, w)| w).sum();
+                for (q, w) in area {
+                    sim.heat_supply[q] += heat * w / sum / params.sim.secs_per_day;
+                }
+            }
+            BuildingEffect::Fertilize { fertility } => {
+                for (q, w) in attrs.effect_area(size, p) {
+                    sim.fertility[q] += fertility * w;
+                }
+            }
+            _ => apply_global_effect(planet, sim, effect, 1.0, params),
         }
     }
 }
 
-fn apply_building_effect(
+/// Apply the effect of n buildings which is not bound to a tile
+fn apply_global_effect(
     planet: &mut Planet,
-    stopped_buildings: &FnvHashMap<BuildingKind, u32>,
+    sim: &mut Sim,
+    effect: &BuildingEffect,
+    n: f32,
     params: &Params,
 ) {
-    for (&orbital_building_kind, Building { enabled, .. }) in &planet.orbit {
-        if let Some(effect) = &params.orbital_buildings[&orbital_building_kind].effect {
-            let n = enabled
-                - stopped_buildings
-                    .get(&BuildingKind::Orbital(orbital_building_kind))
-                    .copied()
-                    .unwrap_or(0);
-
-            match effect {
-                BuildingEffect::SprayToAtmo { kind, mass } => {
-                    *planet.atmo.mass.get_mut(kind).unwrap() += mass * n as f32;
+    match effect {
+        BuildingEffect::SprayToAtmo { kind, mass } => {
+            *planet.atmo.mass.get_mut(kind).unwrap() += mass * n;
+        }
+        BuildingEffect::ConvertGas { from, to, mass } => {
+            convert_gas(&mut planet.atmo, *from, *to, mass * n);
+        }
+        BuildingEffect::Heater { heat } => {
+            let power = heat * n / params.sim.secs_per_day / sim.n_tile as f32;
+            for p in planet.map.iter_idx() {
+                sim.heat_supply[p] += power;
+            }
+        }
+        BuildingEffect::Insolation { ratio, latitude } => {
+            for (y, factor) in sim.insolation_factor.iter_mut().enumerate() {
+                let row_latitude = planet
+                    .calc_longitude_latitude(Coords(0, y as i32))
+                    .1
+                    .to_degrees()
+                    .abs();
+                let in_range = if let Some((min, max)) = latitude {
+                    (*min..=*max).contains(&row_latitude)
+                } else {
+                    true
+                };
+                if in_range {
+                    *factor += ratio * n;
                 }
-                BuildingEffect::Heater { .. } => (),
             }
         }
+        BuildingEffect::DimStar { ratio } => {
+            sim.star_dimming += ratio * n;
+        }
+        BuildingEffect::Fertilize { .. } => (),
+        BuildingEffect::Storage { cap } => {
+            for (kind, v) in cap {
+                *planet.res.cap.get_mut(kind).unwrap() += v * n;
+            }
+        }
+    }
+}
+
+/// Convert the gas to another gas in the same number of moles
+fn convert_gas(atmo: &mut Atmosphere, from: GasKind, to: GasKind, mass: f32) {
+    let from_mass = atmo.mass.get_mut(&from).unwrap();
+    let mass = mass.min(*from_mass);
+    *from_mass -= mass;
+    *atmo.mass.get_mut(&to).unwrap() += mass * to.molar_mass() / from.molar_mass();
+}
+
+impl StructureAttrs {
+    /// Tiles affected by the structure at p and the weight of the effect
+    pub fn effect_area(&self, size: (u32, u32), p: Coords) -> Vec<(Coords, f32)> {
+        tiles_in_radius(size, p, self.range)
+            .into_iter()
+            .map(|(q, d)| {
+                let w = match self.falloff {
+                    Falloff::Uniform => 1.0,
+                    Falloff::Linear => 1.0 - d / (self.range as f32 + 1.0),
+                };
+                (q, w)
+            })
+            .collect()
     }
 }
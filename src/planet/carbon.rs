@@ -0,0 +1,47 @@
+use super::hydro::FREEZING_POINT;
+use super::*;
+
+/// Simulate the geological carbon cycle between the atmosphere, rocks and oceans
+pub fn sim_carbon_cycle(planet: &mut Planet, sim: &Sim, params: &Params) {
+    let kg_to_mt = sim.tile_area * 1.0E-9;
+    let co2_pressure = planet.atmo.partial_pressure(GasKind::CarbonDioxide, params);
+
+    // Silicate weathering on land tiles fixes CO2 as carbonate rocks
+    let co2_factor = (co2_pressure / params.sim.weathering_ref_co2).sqrt();
+    let mut weathered = 0.0;
+    for p in planet.map.iter_idx() {
+        let tile = &planet.map[p];
+        if tile.biome == Biome::Ocean || tile.surface_temp < FREEZING_POINT {
+            continue;
+        }
+        weathered += params.sim.weathering_rate
+            * kg_to_mt
+            * co2_factor
+            * ((tile.surface_temp - params.sim.weathering_ref_temp)
+                / params.sim.weathering_temp_scale)
+                .exp();
+    }
+
+    // Dissolution into and degassing from ocean water
+    let mut dissolved = 0.0;
+    for p in planet.map.iter_idx() {
+        let tile = &mut planet.map[p];
+        let equilibrium = if tile.biome == Biome::Ocean {
+            co2_solubility(tile.surface_temp, params) * co2_pressure * tile.water
+        } else {
+            0.0
+        };
+        let d = params.sim.ocean_co2_exchange_rate * (equilibrium - tile.dissolved_co2);
+        tile.dissolved_co2 += d;
+        dissolved += d * kg_to_mt;
+    }
+
+    let co2 = planet.atmo.mass.get_mut(&GasKind::CarbonDioxide).unwrap();
+    *co2 = (*co2 + params.sim.volcanic_outgassing - weathered - dissolved).max(0.0);
+}
+
+/// CO2 solubility in sea water [kg/(m^3*atm)]
+fn co2_solubility(temp: f32, params: &Params) -> f32 {
+    params.sim.ocean_co2_solubility
+        * (-(temp - FREEZING_POINT) / params.sim.ocean_co2_solubility_temp_scale).exp()
+}
@@ -1,3 +1,4 @@
+use super::event_card::EventCard;
 use fnv::FnvHashMap;
 use geom::Coords;
 use serde::{Deserialize, Serialize};
@@ -12,8 +13,16 @@ pub struct PlanetBasics {
     pub density: f32,
     /// Planet radius [m]
     pub radius: f32,
-    /// Solar constant at the planet [W/m^2]
+    /// Solar constant at the semi-major axis of the planet orbit [W/m^2]
     pub solar_constant: f32,
+    /// Axial tilt [deg]
+    pub axial_tilt: f32,
+    /// Orbital period [days]
+    pub orbital_period: f32,
+    /// Orbital eccentricity
+    pub eccentricity: f32,
+    /// Sidereal rotation period [days]. Tidally locked if equal to the orbital period
+    pub rotation_period: f32,
 }
 
 #[derive(
@@ -63,6 +72,7 @@ pub enum Biome {
     Ocean,
     Desert,
     Grassland,
+    IceSheet,
 }
 
 impl Default for Biome {
@@ -74,6 +84,37 @@ impl Default for Biome {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BiomeAttrs {
     pub z: f32,
+    /// Albedo of bare ground of this biome
+    pub albedo: f32,
+    /// Maximum biomass density this biome can support [kg/m^2]
+    pub max_biomass: f32,
+    /// Heat capacity of the surface [J/(K*m^2)]
+    pub heat_cap: f32,
+    /// Biomes with higher priority are checked first in biome transition
+    #[serde(default)]
+    pub priority: u32,
+    /// Conditions of tiles to transition to this biome
+    #[serde(default)]
+    pub requirements: BiomeRequirements,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BiomeRequirements {
+    /// Temperature range [K]
+    #[serde(default, with = "serde_with::rust::unwrap_or_skip")]
+    pub temp: Option<(f32, f32)>,
+    /// Water depth range [m]
+    #[serde(default, with = "serde_with::rust::unwrap_or_skip")]
+    pub water: Option<(f32, f32)>,
+    /// Soil moisture range [m]
+    #[serde(default, with = "serde_with::rust::unwrap_or_skip")]
+    pub soil_moisture: Option<(f32, f32)>,
+    /// Ice depth range [m]
+    #[serde(default, with = "serde_with::rust::unwrap_or_skip")]
+    pub ice: Option<(f32, f32)>,
+    /// Biomass density range [kg/m^2]
+    #[serde(default, with = "serde_with::rust::unwrap_or_skip")]
+    pub biomass: Option<(f32, f32)>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -85,6 +126,12 @@ pub struct StructureAttrs {
     pub columns: usize,
     pub rows: usize,
     pub building: BuildingAttrs,
+    /// Range of the effect [tiles]. Only the tile of the structure is affected if zero
+    #[serde(default)]
+    pub range: u32,
+    /// How the effect weakens with distance in the range
+    #[serde(default)]
+    pub falloff: Falloff,
 }
 
 impl AsRef<BuildingAttrs> for StructureAttrs {
@@ -100,6 +147,16 @@ pub enum StructureSize {
     Middle,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Falloff {
+    /// The same effect in the range
+    #[default]
+    Uniform,
+    /// The effect decreases linearly with distance
+    Linear,
+}
+
 impl StructureSize {
     /// Additional occupiied tiles by a structure
     pub fn occupied_tiles(&self) -> Vec<Coords> {
@@ -136,6 +193,7 @@ pub enum Structure {
     OxygenGenerator,
     FertilizationPlant,
     Heater,
+    Warehouse,
 }
 
 impl Structure {
@@ -165,6 +223,32 @@ pub enum GasKind {
     Oxygen,
     Nitrogen,
     CarbonDioxide,
+    WaterVapor,
+}
+
+/// Priority of buildings to take resources for upkeep
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Debug,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    AsRefStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -199,6 +283,10 @@ pub struct BuildingAttrs {
 pub enum OrbitalBuildingKind {
     FusionReactor,
     NitrogenSprayer,
+    OrbitalMirror,
+    Sunshade,
+    OrbitalHeater,
+    OrbitalDepot,
 }
 
 #[derive(
@@ -224,21 +312,64 @@ pub enum StarSystemBuildingKind {
     AmmoniaExtractor,
 }
 
-#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize, AsRefStr)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, AsRefStr)]
 #[strum(serialize_all = "kebab-case")]
 pub enum BuildingEffect {
-    SprayToAtmo { kind: GasKind, mass: f32 },
-    Heater { heat: f32 },
+    SprayToAtmo {
+        kind: GasKind,
+        mass: f32,
+    },
+    /// Convert a gas in the atmosphere to another gas
+    ConvertGas {
+        from: GasKind,
+        to: GasKind,
+        /// Mass of the source gas converted per day [Mt]
+        mass: f32,
+    },
+    /// Supply heat to tiles in the range. Orbital buildings heat all tiles equally
+    Heater {
+        /// Heat supplied per day [J]
+        heat: f32,
+    },
+    /// Reflect sunlight to tiles, or shade them by a negative ratio
+    Insolation {
+        /// Ratio of insolation added by a building
+        ratio: f32,
+        /// Range of absolute latitude of affected tiles in both hemispheres [deg].
+        /// All tiles are affected if not specified
+        #[serde(default, with = "serde_with::rust::unwrap_or_skip")]
+        latitude: Option<(f32, f32)>,
+    },
+    /// Dim the star by collecting its light. Applied only if the player turns it on
+    DimStar {
+        /// Ratio of starlight collected by a building
+        ratio: f32,
+    },
+    /// Boost fertility of tiles around the structure
+    Fertilize {
+        /// Fertility added to tiles in the range
+        fertility: f32,
+    },
+    /// Increase the capacity of resources
+    Storage {
+        /// Capacity added by a building
+        cap: ResourceMap,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Params {
     pub start: StartParams,
     pub sim: SimParams,
+    pub habitability: HabitabilityParams,
     #[serde(skip)]
     pub biomes: FnvHashMap<Biome, BiomeAttrs>,
     #[serde(skip)]
     pub structures: FnvHashMap<StructureKind, StructureAttrs>,
+    #[serde(skip)]
+    pub natural_events: NaturalEventParams,
+    #[serde(skip)]
+    pub event_cards: Vec<EventCard>,
     pub orbital_buildings: FnvHashMap<OrbitalBuildingKind, BuildingAttrs>,
     pub star_system_buildings: FnvHashMap<StarSystemBuildingKind, BuildingAttrs>,
 }
@@ -247,12 +378,29 @@ pub struct Params {
 pub struct StartParams {
     pub basics: PlanetBasics,
     pub default_size: (u32, u32),
+    pub default_gen_conf: GenConf,
     pub resources: ResourceMap,
+    /// Capacity of resources without storage buildings
+    pub resource_cap: ResourceMap,
     pub atmo_mass: FnvHashMap<GasKind, f32>,
     pub orbital_buildings: FnvHashMap<OrbitalBuildingKind, u32>,
     pub star_system_buildings: FnvHashMap<StarSystemBuildingKind, u32>,
 }
 
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct GenConf {
+    /// Random seed. The same seed generates the same planet
+    pub seed: u64,
+    /// Sea level [m]
+    pub sea_level: f32,
+    /// Terrain roughness (0.0 ~ 1.0)
+    pub roughness: f32,
+    /// The number of continents
+    pub n_continents: u32,
+    /// Maximum height and depth of the terrain [m]
+    pub max_height: f32,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SimParams {
     pub sim_normal_loop_duration_ms: u64,
@@ -261,12 +409,149 @@ pub struct SimParams {
     pub secs_per_day: f32,
     /// Heat capacity of air [J/(kg*K)]
     pub air_heat_cap: f32,
-    /// Heat capacity of planet surface [J/(kg*m^3)]
-    pub surface_heat_cap: f32,
-    /// Factor to average sunlight power per day
-    pub sunlight_day_averaging_factor: f32,
     /// The ratio of tile air diffusion
     pub air_diffusion_factor: f32,
+    /// Coefficient of sensible heat exchange between the surface and air [W/(m^2*K)]
+    pub sensible_heat_exchange_coef: f32,
     /// The number of loop of atmosphere heat transfer calculation
     pub n_loop_atmo_heat_calc: usize,
+    /// Acceleration of air by temperature gradient [m^2/(s^2*K)]
+    pub wind_pressure_factor: f32,
+    /// Friction coefficient of wind near the surface [1/s]
+    pub wind_friction: f32,
+    /// Speed of zonal winds at the center of each circulation cell [m/s]
+    pub wind_zonal_speed: f32,
+    /// Maximum wind speed [m/s]
+    pub wind_max_speed: f32,
+    /// Simulate gas concentration of each tile
+    pub per_tile_gas: bool,
+    /// The ratio of gas mixing between adjacent tiles per day
+    pub gas_mixing_factor: f32,
+    /// Simulate the day and night cycle instead of daily mean insolation
+    pub diurnal_cycle: bool,
+    /// The number of simulation steps per day when the day and night cycle is enabled
+    pub n_steps_per_day: u32,
+    /// Albedo of tiles fully covered by vegetation
+    pub vegetation_albedo: f32,
+    /// Biomass density needed to cover a tile by vegetation fully [kg/m^2]
+    pub biomass_full_cover: f32,
+    /// Albedo of tiles fully covered by ice
+    pub ice_albedo: f32,
+    /// Ice depth needed to cover a tile fully [m]
+    pub ice_full_cover: f32,
+    /// Albedo of clouds
+    pub cloud_albedo: f32,
+    /// Cloud cover of tiles at saturated humidity
+    pub max_cloud_cover: f32,
+    /// Rate of freezing and melting of water [m/(K*day)]
+    pub freezing_rate: f32,
+    /// Rate of evaporation by the vapor pressure deficit [m/(atm*day)]
+    pub evaporation_rate: f32,
+    /// Rate of condensation by the vapor pressure excess [m/(atm*day)]
+    pub condensation_rate: f32,
+    /// The ratio of water flow to lower tiles per day
+    pub water_flow_rate: f32,
+    /// Maximum depth of water held in soil [m]
+    pub soil_field_capacity: f32,
+    /// Maximum growth rate of biomass [1/day]
+    pub biomass_growth_rate: f32,
+    /// Death rate of biomass under unsuitable conditions [1/day]
+    pub biomass_death_rate: f32,
+    /// Biomass density of spores, which is the origin of growth on bare tiles [kg/m^2]
+    pub biomass_seed: f32,
+    /// Minimum, optimum and maximum temperature for biomass growth [K]
+    pub biomass_growth_temp: (f32, f32, f32),
+    /// Minimum atmospheric pressure for biomass growth [atm]
+    pub biomass_min_atm: f32,
+    /// CO2 partial pressure that gives half of the maximum growth [atm]
+    pub biomass_co2_half_saturation: f32,
+    /// O2 partial pressure that gives half of the maximum growth [atm]
+    pub biomass_o2_half_saturation: f32,
+    /// Daily mean insolation that gives half of the maximum growth [W/m^2]
+    pub biomass_light_half_saturation: f32,
+    /// Soil moisture that gives half of the maximum growth [m]
+    pub biomass_water_half_saturation: f32,
+    /// CO2 released by volcanoes [Mt/day]
+    pub volcanic_outgassing: f32,
+    /// CO2 fixed by silicate weathering on land at the reference condition [kg/(m^2*day)]
+    pub weathering_rate: f32,
+    /// Reference CO2 partial pressure of silicate weathering [atm]
+    pub weathering_ref_co2: f32,
+    /// Reference temperature of silicate weathering [K]
+    pub weathering_ref_temp: f32,
+    /// Temperature difference that changes weathering rate by a factor of e [K]
+    pub weathering_temp_scale: f32,
+    /// CO2 solubility in sea water at the freezing point [kg/(m^3*atm)]
+    pub ocean_co2_solubility: f32,
+    /// Temperature difference that changes CO2 solubility by a factor of e [K]
+    pub ocean_co2_solubility_temp_scale: f32,
+    /// The ratio of CO2 exchange between oceans and the atmosphere per day
+    pub ocean_co2_exchange_rate: f32,
+    /// Ratio of exosphere temperature to the mean surface temperature
+    pub exosphere_temp_factor: f32,
+    /// Coefficient of Jeans escape of gases [1/day]
+    pub atmo_escape_factor: f32,
+    /// The ratio of dust settling from the atmosphere per day
+    pub dust_settling_rate: f32,
+    /// Interval of biome transition [days]
+    pub biome_transition_interval_days: u64,
+    /// Greenhouse effect optical depth of each gas per partial pressure [1/atm]
+    pub greenhouse_optical_depth: FnvHashMap<GasKind, f32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HabitabilityParams {
+    /// Minimum, optimum and maximum air temperature [K]
+    pub temp: (f32, f32, f32),
+    /// Minimum, optimum and maximum atmospheric pressure [atm]
+    pub atm: (f32, f32, f32),
+    /// Minimum, optimum and maximum O2 partial pressure [atm]
+    pub o2: (f32, f32, f32),
+    /// Soil moisture that gives half of the water factor [m]
+    pub water_half_saturation: f32,
+    /// Tiles under deeper water than this are not habitable [m]
+    pub max_water_depth: f32,
+    /// Biomass density that gives half of the biomass factor [kg/m^2]
+    pub biomass_half_saturation: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NaturalEventParams {
+    pub meteor_impact: MeteorImpactParams,
+    pub solar_flare: SolarFlareParams,
+    pub volcanic_eruption: VolcanicEruptionParams,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MeteorImpactParams {
+    /// Probability of occurrence per day
+    pub probability: f32,
+    /// Minimum and maximum radius of craters [tiles]
+    pub crater_radius: (u32, u32),
+    /// Depth of craters at the center [m]
+    pub crater_depth: f32,
+    /// Optical depth of dust thrown into the atmosphere
+    pub dust: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SolarFlareParams {
+    /// Probability of occurrence per day
+    pub probability: f32,
+    /// The ratio of stocked energy lost by a flare
+    pub energy_loss: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VolcanicEruptionParams {
+    /// Probability of occurrence per day
+    pub probability: f32,
+    /// CO2 released by an eruption [Mt]
+    pub co2: f32,
+    /// Heat released to the surface around the volcano [J]
+    pub heat: f32,
+    /// Radius of the heated area [tiles]
+    pub radius: u32,
+    /// Optical depth of dust thrown into the atmosphere
+    pub dust: f32,
 }
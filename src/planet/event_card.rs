@@ -0,0 +1,135 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+
+/// An event card shown to the player when its conditions are satisfied
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventCard {
+    pub id: String,
+    /// Text key of the title
+    pub title: String,
+    /// Text key of the description
+    pub text: String,
+    /// All conditions must be satisfied to trigger this card
+    #[serde(default)]
+    pub conditions: Vec<EventCondition>,
+    pub choices: Vec<EventChoice>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCondition {
+    Gt(EventValue, f32),
+    Lt(EventValue, f32),
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventValue {
+    Days,
+    /// Atmospheric pressure [atm]
+    Atm,
+    /// Partial pressure of a gas [atm]
+    PartialPressure(GasKind),
+    /// Stock of a resource
+    Stock(ResourceKind),
+    /// Mean air temperature [K]
+    Temp,
+    /// Mean habitability of tiles
+    Habitability,
+    /// Earth Similarity Index
+    EarthSimilarityIndex,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventChoice {
+    /// Text key of the choice
+    pub text: String,
+    /// Changes of resource stock
+    #[serde(default)]
+    pub resources: ResourceMap,
+    /// Changes of gas mass in the atmosphere [Mt]
+    #[serde(default)]
+    pub atmo: FnvHashMap<GasKind, f32>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventCardState {
+    /// Cards already triggered. Each card is triggered only once
+    pub fired: BTreeSet<String>,
+    /// Card waiting for the player's choice
+    pub pending: Option<String>,
+}
+
+impl EventCondition {
+    pub fn check(&self, planet: &Planet, sim: &Sim, params: &Params) -> bool {
+        match *self {
+            EventCondition::Gt(value, threshold) => value.get(planet, sim, params) > threshold,
+            EventCondition::Lt(value, threshold) => value.get(planet, sim, params) < threshold,
+        }
+    }
+}
+
+impl EventValue {
+    fn get(&self, planet: &Planet, sim: &Sim, params: &Params) -> f32 {
+        match *self {
+            EventValue::Days => planet.days as f32,
+            EventValue::Atm => planet.atmo.atm,
+            EventValue::PartialPressure(kind) => planet.atmo.partial_pressure(kind, params),
+            EventValue::Stock(kind) => planet.res.stock[&kind],
+            EventValue::Temp => planet.mean_temp(),
+            EventValue::Habitability => sim.mean_habitability(),
+            EventValue::EarthSimilarityIndex => planet.earth_similarity_index(params),
+        }
+    }
+}
+
+/// Trigger the first card whose conditions are satisfied if no card is pending
+pub fn check_event_cards(planet: &mut Planet, sim: &Sim, params: &Params) {
+    if planet.pending_event_card(params).is_some() {
+        return;
+    }
+
+    let Some(card) = params.event_cards.iter().find(|card| {
+        !planet.event_cards.fired.contains(&card.id)
+            && card
+                .conditions
+                .iter()
+                .all(|condition| condition.check(planet, sim, params))
+    }) else {
+        return;
+    };
+
+    planet.event_cards.fired.insert(card.id.clone());
+    planet.event_cards.pending = Some(card.id.clone());
+}
+
+impl Planet {
+    /// Card waiting for the player's choice
+    pub fn pending_event_card<'a>(&self, params: &'a Params) -> Option<&'a EventCard> {
+        let id = self.event_cards.pending.as_ref()?;
+        params.event_cards.iter().find(|card| &card.id == id)
+    }
+
+    /// Apply the outcome of the chosen choice of the pending card, and close it
+    pub fn choose_event_card(&mut self, i: usize, params: &Params) {
+        let Some(choice) = self
+            .pending_event_card(params)
+            .and_then(|card| card.choices.get(i))
+        else {
+            self.event_cards.pending = None;
+            return;
+        };
+
+        for (kind, v) in &choice.resources {
+            let stock = self.res.get_stock_mut(*kind);
+            *stock = (*stock + v).max(0.0);
+        }
+        for (kind, v) in &choice.atmo {
+            let mass = self.atmo.mass.get_mut(kind).unwrap();
+            *mass = (*mass + v).max(0.0);
+        }
+        self.atmo.atm = self.atmo.total_mass() / params.sim.total_mass_per_atm;
+
+        self.event_cards.pending = None;
+    }
+}
@@ -0,0 +1,137 @@
+use super::*;
+
+/// Simulate gas concentration of tiles. Gases from local sources spread by mixing and wind
+pub fn sim_gas_field(planet: &Planet, sim: &mut Sim, params: &Params) {
+    if !params.sim.per_tile_gas {
+        return;
+    }
+
+    let sources = local_sources(planet, sim, params);
+    let size = planet.map.size();
+    let mut buf = Array2d::new(size.0, size.1, 0.0);
+
+    // Mixing is divided into sub-steps to keep it stable around the poles
+    let mixing = 0.25 * params.sim.gas_mixing_factor;
+    let n_substeps = (mixing * sim.max_conductance_sum / 0.5).ceil().max(1.0) as u32;
+    let mixing = mixing / n_substeps as f32;
+
+    for (&kind, total) in &planet.atmo.mass {
+        let mut field = sim.gas.remove(&kind).unwrap();
+
+        let kind_sources: Vec<_> = sources
+            .iter()
+            .filter(|(_, source_kind, _)| *source_kind == kind)
+            .map(|(p, _, mass)| (*p, *mass))
+            .collect();
+        distribute(&mut field, *total, &kind_sources);
+
+        // Mixing with adjacent tiles
+        for _ in 0..n_substeps {
+            for p in planet.map.iter_idx() {
+                buf[p] = sim
+                    .adjacent_conductance(size, p)
+                    .into_iter()
+                    .map(|(adjacent, g)| mixing * g * (field[adjacent] - field[p]))
+                    .sum();
+            }
+            for p in planet.map.iter_idx() {
+                field[p] += buf[p];
+            }
+        }
+
+        // Transport by wind
+        self::wind::calc_advection(planet, &sim.wind, &field, &mut buf, params.sim.secs_per_day);
+        for p in planet.map.iter_idx() {
+            field[p] = (field[p] + buf[p]).max(0.0);
+        }
+
+        // Keep the sum of tiles consistent with the global mass
+        distribute(&mut field, *total, &[]);
+        sim.gas.insert(kind, field);
+    }
+}
+
+/// Working structures releasing gases to the atmosphere
+fn local_sources(planet: &Planet, sim: &Sim, params: &Params) -> Vec<(Coords, GasKind, f32)> {
+    let mut sources = Vec::new();
+    for p in planet.map.iter_idx() {
+        if sim.stopped_structures[p] {
+            continue;
+        }
+        let Some(attrs) = params.structures.get(&planet.map[p].structure.kind()) else {
+            continue;
+        };
+        match attrs.building.effect {
+            Some(BuildingEffect::SprayToAtmo { kind, mass }) => {
+                sources.push((p, kind, mass));
+            }
+            Some(BuildingEffect::ConvertGas { from, to, mass }) => {
+                sources.push((p, to, mass * to.molar_mass() / from.molar_mass()));
+            }
+            _ => (),
+        }
+    }
+    sources
+}
+
+/// Adjust tile gas mass to the given total. The increase is given to the sources first,
+/// and the rest of the difference is distributed in proportion to the current mass
+fn distribute(field: &mut Array2d<f32>, total: f32, sources: &[(Coords, f32)]) {
+    let mut delta = total - field.iter().sum::<f32>();
+
+    let source_total: f32 = sources.iter().map(|(_, mass)| mass).sum();
+    if delta > 0.0 && source_total > 0.0 {
+        let ratio = (delta / source_total).min(1.0);
+        for &(p, mass) in sources {
+            field[p] += mass * ratio;
+        }
+        delta -= source_total * ratio;
+    }
+
+    let sum: f32 = field.iter().sum();
+    if sum > 0.0 {
+        let ratio = ((sum + delta) / sum).max(0.0);
+        for p in field.iter_idx() {
+            field[p] *= ratio;
+        }
+    } else {
+        let (w, h) = field.size();
+        let v = total / (w * h) as f32;
+        for p in field.iter_idx() {
+            field[p] = v;
+        }
+    }
+}
+
+impl Sim {
+    /// Partial pressure of the given gas at the tile [atm]
+    pub fn local_partial_pressure(&self, kind: GasKind, p: Coords, params: &Params) -> f32 {
+        self.gas[&kind][p] * self.n_tile as f32 / params.sim.total_mass_per_atm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribute_keeps_total() {
+        let mut field = Array2d::new(8, 4, 0.0);
+        for p in field.iter_idx() {
+            field[p] = (p.0 + 2 * p.1) as f32;
+        }
+        let sources = [(Coords(1, 1), 5.0), (Coords(6, 2), 10.0)];
+
+        for total in [200.0, 100.0, 10.0] {
+            distribute(&mut field, total, &sources);
+            let sum: f32 = field.iter().sum();
+            assert!((sum - total).abs() < 1.0E-3 * total, "{sum} != {total}");
+            assert!(field.iter().all(|v| *v >= 0.0));
+        }
+
+        // The increase is given to the sources first
+        let before = field[Coords(1, 1)];
+        distribute(&mut field, 12.0, &sources);
+        assert!((field[Coords(1, 1)] - before - 5.0 / 15.0 * 2.0).abs() < 1.0E-3);
+    }
+}
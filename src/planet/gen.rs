@@ -0,0 +1,122 @@
+use super::*;
+
+const N_OCTAVES: u32 = 6;
+const BASE_FREQ: u32 = 4;
+
+pub fn generate(planet: &mut Planet, conf: &GenConf) {
+    let (w, h) = planet.map.size();
+
+    let continents: Vec<(f32, f32, f32)> = (0..conf.n_continents)
+        .map(|i| {
+            let i = i as u64;
+            let x = hash_f32(conf.seed, i, 0, 1) * w as f32;
+            let y = (0.2 + 0.6 * hash_f32(conf.seed, i, 0, 2)) * h as f32;
+            let r = (0.08 + 0.1 * hash_f32(conf.seed, i, 0, 3)) * w as f32;
+            (x, y, r)
+        })
+        .collect();
+
+    for p in planet.map.iter_idx() {
+        let (x, y) = (p.0 as f32 + 0.5, p.1 as f32 + 0.5);
+
+        // Continents raise the terrain around their centers
+        let continent: f32 = continents
+            .iter()
+            .map(|&(cx, cy, r)| {
+                let dx = (x - cx).abs();
+                let dx = dx.min(w as f32 - dx);
+                let dy = y - cy;
+                (-(dx * dx + dy * dy) / (r * r)).exp()
+            })
+            .fold(0.0, f32::max);
+
+        let noise = fractal_noise(conf, x / w as f32, y / h as f32, w, h);
+        let height = (continent * 1.5 - 0.8 + (0.5 + conf.roughness) * noise).clamp(-1.0, 1.0);
+
+        let tile = &mut planet.map[p];
+        tile.height = height * conf.max_height;
+        if tile.height < conf.sea_level {
+            tile.biome = Biome::Ocean;
+            tile.water = conf.sea_level - tile.height;
+        } else {
+            tile.biome = Biome::Rock;
+        }
+    }
+}
+
+/// Fractal value noise (-1.0 ~ 1.0) which is cyclic on X axis
+fn fractal_noise(conf: &GenConf, u: f32, v: f32, w: u32, h: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut total_amplitude = 0.0;
+
+    for octave in 0..N_OCTAVES {
+        let nx = BASE_FREQ << octave;
+        let ny = ((nx * h) / w).max(1);
+        sum += amplitude * value_noise(conf.seed, octave, u * nx as f32, v * ny as f32, nx);
+        total_amplitude += amplitude;
+        amplitude *= conf.roughness;
+    }
+
+    (2.0 * sum / total_amplitude).clamp(-1.0, 1.0)
+}
+
+fn value_noise(seed: u64, octave: u32, x: f32, y: f32, period_x: u32) -> f32 {
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = smoothstep(x - x0);
+    let fy = smoothstep(y - y0);
+    let x0 = x0 as u32 % period_x;
+    let x1 = (x0 + 1) % period_x;
+    let y0 = y0 as u64;
+    let octave = octave as u64;
+
+    let lattice = |x: u32, y: u64| hash_f32(seed, octave, x as u64, y + 1024) * 2.0 - 1.0;
+    let a = lattice(x0, y0) * (1.0 - fx) + lattice(x1, y0) * fx;
+    let b = lattice(x0, y0 + 1) * (1.0 - fx) + lattice(x1, y0 + 1) * fx;
+    a * (1.0 - fy) + b * fy
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Hash the given values to 0.0 ~ 1.0
+fn hash_f32(seed: u64, a: u64, b: u64, c: u64) -> f32 {
+    let mut x = seed;
+    for v in [a, b, c] {
+        x = splitmix64(x ^ v);
+    }
+    (x >> 40) as f32 / (1u64 << 24) as f32
+}
+
+pub fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heights(conf: &GenConf) -> Vec<f32> {
+        let params: Params = ron::from_str(include_str!("../../assets/planet.params.ron")).unwrap();
+        let planet = Planet::new(32, 16, &params.start, conf);
+        planet.map.iter().map(|tile| tile.height).collect()
+    }
+
+    #[test]
+    fn same_seed_generates_same_planet() {
+        let params: Params = ron::from_str(include_str!("../../assets/planet.params.ron")).unwrap();
+        let mut conf = params.start.default_gen_conf;
+        conf.seed = 42;
+
+        assert_eq!(heights(&conf), heights(&conf));
+
+        let mut other = conf.clone();
+        other.seed = 43;
+        assert_ne!(heights(&conf), heights(&other));
+    }
+}
@@ -0,0 +1,104 @@
+use super::*;
+use geom::CyclicMode;
+
+// The map is a Lambert cylindrical equal-area projection. Rows are uniform in the sine of
+// latitude, so all tiles have the same area, but tiles become narrow and tall near the poles.
+
+/// Adjacent tile in the given direction on the sphere.
+/// Crossing a pole leads to the tile at the opposite longitude in the same row
+pub fn adjacent_tile(size: (u32, u32), p: Coords, d: Coords) -> Coords {
+    let (nx, ny) = (size.0 as i32, size.1 as i32);
+    let (x, y) = (p.0 + d.0, p.1 + d.1);
+    if y < 0 || y >= ny {
+        Coords((x + nx / 2).rem_euclid(nx), p.1)
+    } else {
+        Coords(x.rem_euclid(nx), y)
+    }
+}
+
+/// Tiles within the radius and the distance to them
+pub fn tiles_in_radius(size: (u32, u32), p: Coords, r: u32) -> Vec<(Coords, f32)> {
+    let r = r as i32;
+    let mut tiles = Vec::new();
+    for dy in -r..=r {
+        for dx in -r..=r {
+            let d = ((dx * dx + dy * dy) as f32).sqrt();
+            if d > r as f32 {
+                continue;
+            }
+            if let Some(q) = CyclicMode::X.convert_coords(size, p + Coords(dx, dy)) {
+                tiles.push((q, d));
+            }
+        }
+    }
+    tiles
+}
+
+/// Width and height of tiles at the latitude [m]
+pub fn tile_size(planet: &Planet, latitude: f32) -> (f32, f32) {
+    let (nx, ny) = planet.map.size();
+    let radius = planet.basics.radius;
+    let cos = latitude.cos();
+    (
+        2.0 * PI * radius * cos / nx as f32,
+        2.0 * radius / (ny as f32 * cos),
+    )
+}
+
+/// Conductance between adjacent tiles, which is the ratio of the length of their
+/// boundary to the distance between their centers.
+/// Returns conductance to east and west tiles for each row, and conductance to north tiles
+/// for each row. The north of the last row is the boundary across the north pole.
+pub fn calc_conductance(planet: &Planet) -> (Vec<f32>, Vec<f32>) {
+    let (nx, ny) = planet.map.size();
+    let latitude = |y: i32| planet.calc_longitude_latitude(Coords(0, y)).1;
+
+    let ew = (0..ny as i32)
+        .map(|y| {
+            let cos = latitude(y).cos();
+            nx as f32 / (PI * ny as f32 * cos * cos)
+        })
+        .collect();
+
+    let ns = (0..ny as i32)
+        .map(|y| {
+            if y + 1 < ny as i32 {
+                let boundary_latitude = (2.0 * (y + 1) as f32 / ny as f32 - 1.0).asin();
+                let boundary = 2.0 * PI * boundary_latitude.cos() / nx as f32;
+                boundary / (latitude(y + 1) - latitude(y))
+            } else {
+                // Tiles around the pole are connected through the pole
+                pole_conductance(latitude(y), nx)
+            }
+        })
+        .collect();
+
+    (ew, ns)
+}
+
+/// Conductance between tiles at the opposite longitudes across a pole
+pub fn pole_conductance(latitude: f32, nx: u32) -> f32 {
+    let width = 2.0 * PI * latitude.cos() / nx as f32;
+    width / (2.0 * (PI / 2.0 - latitude.abs()))
+}
+
+impl Sim {
+    /// Adjacent tiles in four directions and conductance to them
+    pub fn adjacent_conductance(&self, size: (u32, u32), p: Coords) -> [(Coords, f32); 4] {
+        let y = p.1 as usize;
+        let south = if y > 0 {
+            self.ns_conductance[y - 1]
+        } else {
+            self.south_pole_conductance
+        };
+        [
+            (adjacent_tile(size, p, Coords(1, 0)), self.ew_conductance[y]),
+            (
+                adjacent_tile(size, p, Coords(-1, 0)),
+                self.ew_conductance[y],
+            ),
+            (adjacent_tile(size, p, Coords(0, 1)), self.ns_conductance[y]),
+            (adjacent_tile(size, p, Coords(0, -1)), south),
+        ]
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+/// Earth radius [m]
+const EARTH_RADIUS: f32 = 6.371E+6;
+/// Earth density [kg/m^3]
+const EARTH_DENSITY: f32 = 5514.0;
+/// Earth escape velocity [m/s]
+const EARTH_ESCAPE_VELOCITY: f32 = 11186.0;
+/// Earth mean surface temperature [K]
+const EARTH_MEAN_TEMP: f32 = 288.0;
+/// Earth atmospheric pressure [atm]
+const EARTH_ATM: f32 = 1.0;
+/// Earth O2 partial pressure [atm]
+const EARTH_O2: f32 = 0.21;
+/// Weight exponents of radius, density, escape velocity, temperature,
+/// atmospheric pressure and O2 partial pressure for ESI
+const ESI_WEIGHTS: [f32; 6] = [0.57, 1.07, 0.70, 5.58, 1.0, 1.0];
+
+pub fn calc_habitability(planet: &Planet, sim: &mut Sim, params: &Params) {
+    for p in planet.map.iter_idx() {
+        sim.habitability[p] = planet.tile_habitability(sim, p, params);
+    }
+}
+
+impl Planet {
+    /// Habitability of the tile for humans (0.0 ~ 1.0)
+    pub fn tile_habitability(&self, sim: &Sim, p: Coords, params: &Params) -> f32 {
+        let hp = &params.habitability;
+        let tile = &self.map[p];
+
+        // Humans live on land
+        if tile.water > hp.max_water_depth {
+            return 0.0;
+        }
+
+        let o2 = if params.sim.per_tile_gas {
+            sim.local_partial_pressure(GasKind::Oxygen, p, params)
+        } else {
+            self.atmo.partial_pressure(GasKind::Oxygen, params)
+        };
+
+        band_factor(tile.temp, hp.temp)
+            * band_factor(self.atmo.atm, hp.atm)
+            * band_factor(o2, hp.o2)
+            * (tile.soil_moisture / (tile.soil_moisture + hp.water_half_saturation))
+            * (tile.biomass / (tile.biomass + hp.biomass_half_saturation))
+    }
+
+    /// Mean air temperature [K]
+    pub fn mean_temp(&self) -> f32 {
+        self.map.iter().map(|tile| tile.temp).sum::<f32>() / self.map.iter().count() as f32
+    }
+
+    /// Earth Similarity Index (0.0 ~ 1.0). In addition to the terms of the planetary ESI,
+    /// atmospheric pressure and O2 are included to measure the progress of terraforming
+    pub fn earth_similarity_index(&self, params: &Params) -> f32 {
+        let values = [
+            (self.basics.radius, EARTH_RADIUS),
+            (self.basics.density, EARTH_DENSITY),
+            (self.basics.escape_velocity(), EARTH_ESCAPE_VELOCITY),
+            (self.mean_temp(), EARTH_MEAN_TEMP),
+            (self.atmo.atm, EARTH_ATM),
+            (
+                self.atmo.partial_pressure(GasKind::Oxygen, params),
+                EARTH_O2,
+            ),
+        ];
+        let n = values.len() as f32;
+
+        values
+            .into_iter()
+            .zip(ESI_WEIGHTS)
+            .map(|((x, x0), w)| (1.0 - ((x - x0) / (x + x0)).abs()).powf(w / n))
+            .product()
+    }
+}
+
+impl Sim {
+    /// Mean habitability of tiles
+    pub fn mean_habitability(&self) -> f32 {
+        self.habitability.iter().sum::<f32>() / self.n_tile as f32
+    }
+}
+
+/// 0.0 outside of the range, and 1.0 at the optimum value
+fn band_factor(value: f32, (min, opt, max): (f32, f32, f32)) -> f32 {
+    if value <= min || value >= max {
+        0.0
+    } else if value < opt {
+        (value - min) / (opt - min)
+    } else {
+        (max - value) / (max - opt)
+    }
+}
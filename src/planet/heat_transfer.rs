@@ -1,9 +1,13 @@
+use super::hydro::saturation_vapor_pressure;
+use super::wind::MAX_COURANT_NUMBER;
 use super::*;
-use geom::{CyclicMode, Direction};
 
 /// Stefan-Boltzmann Constant [W/(m2*K4)]
 pub const STEFAN_BOLTZMANN_CONSTANT: f32 = 5.670E-8;
 
+const MAX_DIFFUSION_PER_STEP: f32 = 0.5;
+const MAX_SUBSTEPS: u32 = 100;
+
 pub fn advance(planet: &mut Planet, sim: &mut Sim, params: &Params) {
     let size = planet.map.size();
     let map_iter_idx = planet.map.iter_idx();
@@ -12,72 +16,176 @@ pub fn advance(planet: &mut Planet, sim: &mut Sim, params: &Params) {
 
     // Calculate heat capacity of tiles
     for p in map_iter_idx {
-        sim.atmo_heat_cap[p] = air_heat_cap_per_tile + params.sim.surface_heat_cap * sim.tile_area;
+        sim.atmo_heat_cap[p] = air_heat_cap_per_tile;
+        sim.surface_heat_cap[p] = params.biomes[&planet.map[p].biome].heat_cap * sim.tile_area;
     }
 
+    // Calculate insolation of tiles
+    self::insolation::calc_insolation(planet, sim, params);
+
     // Calculate albedo of tiles
+    let vapor_pressure = planet.atmo.partial_pressure(GasKind::WaterVapor, params);
     for p in map_iter_idx {
-        sim.albedo[p] = 0.3;
+        let vapor_pressure = if params.sim.per_tile_gas {
+            sim.local_partial_pressure(GasKind::WaterVapor, p, params)
+        } else {
+            vapor_pressure
+        };
+        sim.albedo[p] = calc_albedo(&planet.map[p], vapor_pressure, params);
     }
 
+    // Dust in the atmosphere blocks sunlight
+    let dust_transmittance = (-planet.atmo.dust).exp();
+
+    // Calculate the ratio of longwave radiation escaping to space
+    let transmittance = calc_transmittance(planet, params);
+
     // Set temprature for simulation
     for p in map_iter_idx {
         sim.atemp[p] = planet.map[p].temp;
+        sim.stemp[p] = planet.map[p].surface_temp;
     }
 
-    let secs_per_loop = params.sim.secs_per_day / params.sim.n_loop_atmo_heat_calc as f32;
+    let step_ratio = 1.0 / planet.n_steps_per_day(params) as f32;
+    let secs_per_loop =
+        params.sim.secs_per_day * step_ratio / params.sim.n_loop_atmo_heat_calc as f32;
 
-    // Calculate new atmosphere temprature of tiles
+    // Diffusion ratio of air temperature difference to an adjacent tile per loop
+    let diffusion = 0.5 * params.sim.air_diffusion_factor * step_ratio;
+    let mut max_substeps = 1;
+    let mut transport_limited = false;
+
+    // Calculate new temprature of tiles
     for _ in 0..params.sim.n_loop_atmo_heat_calc {
+        // Transport in the air needs sub-steps if a loop is too long for explicit calculation
+        self::wind::calc_wind(planet, sim, params);
+        let required_substeps = calc_required_substeps(
+            sim.max_conductance_sum * diffusion,
+            self::wind::max_courant_number(planet, sim, secs_per_loop),
+        );
+        let n_substeps = (required_substeps.ceil() as u32).clamp(1, MAX_SUBSTEPS);
+        max_substeps = max_substeps.max(n_substeps);
+
+        // Over the limit of sub-steps, transport is weakened to keep each sub-step stable
+        let weakening = (n_substeps as f32 / required_substeps).min(1.0);
+        if weakening < 1.0 {
+            transport_limited = true;
+        }
+        let secs_per_substep = secs_per_loop / n_substeps as f32 * weakening;
+        let diffusion = diffusion / n_substeps as f32 * weakening;
+
+        for _ in 0..n_substeps {
+            // Heat carried by wind
+            self::wind::calc_advection(
+                planet,
+                &sim.wind,
+                &sim.atemp,
+                &mut sim.atemp_new,
+                secs_per_substep,
+            );
+            for p in map_iter_idx {
+                sim.atemp[p] += sim.atemp_new[p];
+            }
+
+            // Diffusion of air
+            for p in map_iter_idx {
+                let delta_temp: f32 = sim
+                    .adjacent_conductance(size, p)
+                    .into_iter()
+                    .map(|(adjacent_tile, g)| g * (sim.atemp[adjacent_tile] - sim.atemp[p]))
+                    .sum();
+                sim.atemp_new[p] = sim.atemp[p] + diffusion * delta_temp;
+            }
+            std::mem::swap(&mut sim.atemp, &mut sim.atemp_new);
+        }
+
+        // Radiation on the surface. Outgoing radiation is linearized around the current
+        // temprature and solved implicitly, so that strong heating does not make it unstable
         for p in map_iter_idx {
-            let old_heat_amount = sim.atmo_heat_cap[p] * sim.atemp[p];
-
-            let solar_power = planet.basics.solar_constant
-                * planet.calc_longitude_latitude(p).1.cos()
-                * (1.0 - sim.albedo[p])
-                * params.sim.sunlight_day_averaging_factor;
-
-            let inflow = solar_power * sim.tile_area;
-
-            let outflow = STEFAN_BOLTZMANN_CONSTANT * sim.atemp[p].powi(4) * sim.tile_area;
-
-            let adjacent_tile_flow: f32 = Direction::FOUR_DIRS
-                .into_iter()
-                .map(|dir| {
-                    if let Some(adjacent_tile) =
-                        CyclicMode::X.convert_coords(planet.map.size(), p + dir.as_coords())
-                    {
-                        let delta_temp = sim.atemp[adjacent_tile] - sim.atemp[p];
-                        0.5 * params.sim.air_diffusion_factor * air_heat_cap_per_tile * delta_temp
-                    } else {
-                        0.0
-                    }
-                })
-                .sum();
-
-            let structure_heat = if let Some(structure_param) =
-                params.structures.get(&planet.map[p].structure.kind())
-            {
-                if let Some(BuildingEffect::Heater { heat }) = structure_param.building.effect {
-                    heat
-                } else {
-                    0.0
-                }
-            } else {
-                0.0
-            };
-
-            let heat_amount = old_heat_amount
+            let solar_power = sim.insolation[p] * dust_transmittance * (1.0 - sim.albedo[p]);
+
+            let temp = sim.stemp[p];
+            let inflow = solar_power * sim.tile_area + sim.heat_supply[p];
+            let outflow = STEFAN_BOLTZMANN_CONSTANT * temp.powi(4) * transmittance * sim.tile_area;
+            let d_outflow =
+                4.0 * STEFAN_BOLTZMANN_CONSTANT * temp.powi(3) * transmittance * sim.tile_area;
+
+            sim.stemp[p] = temp
                 + (inflow - outflow) * secs_per_loop
-                + adjacent_tile_flow
-                + structure_heat;
-            sim.atemp_new[p] = heat_amount / sim.atmo_heat_cap[p];
+                    / (sim.surface_heat_cap[p] + d_outflow * secs_per_loop);
+        }
+
+        // Sensible heat exchange between the surface and air.
+        // The difference decays exponentially, so this is stable for any heat capacity
+        for p in map_iter_idx {
+            let air_heat_cap = sim.atmo_heat_cap[p];
+            let surface_heat_cap = sim.surface_heat_cap[p];
+            if air_heat_cap <= 0.0 {
+                sim.atemp[p] = sim.stemp[p];
+                continue;
+            }
+            let rate = params.sim.sensible_heat_exchange_coef
+                * sim.tile_area
+                * (1.0 / air_heat_cap + 1.0 / surface_heat_cap);
+            let delta_temp = (sim.stemp[p] - sim.atemp[p]) * (1.0 - (-rate * secs_per_loop).exp());
+            let heat = delta_temp / (1.0 / air_heat_cap + 1.0 / surface_heat_cap);
+            sim.stemp[p] -= heat / surface_heat_cap;
+            sim.atemp[p] += heat / air_heat_cap;
         }
-        std::mem::swap(&mut sim.atemp, &mut sim.atemp_new);
     }
 
+    if max_substeps != sim.n_substeps {
+        if max_substeps > 1 {
+            log::info!("heat transfer needs {} sub-steps per loop", max_substeps);
+        }
+        sim.n_substeps = max_substeps;
+    }
+    if transport_limited && !sim.transport_limited {
+        log::warn!(
+            "heat transfer needs more than {} sub-steps, air transport is weakened",
+            MAX_SUBSTEPS
+        );
+    }
+    sim.transport_limited = transport_limited;
+
     // Set calculated new temprature
     for p in map_iter_idx {
         planet.map[p].temp = sim.atemp[p];
+        planet.map[p].surface_temp = sim.stemp[p];
     }
 }
+
+/// The number of sub-steps to keep explicit diffusion and advection stable
+fn calc_required_substeps(diffusion: f32, courant_number: f32) -> f32 {
+    (diffusion / MAX_DIFFUSION_PER_STEP)
+        .max(courant_number / MAX_COURANT_NUMBER)
+        .max(1.0)
+}
+
+/// Calculate tile albedo from its biome, the vegetation and ice covering it, and clouds over it
+fn calc_albedo(tile: &Tile, vapor_pressure: f32, params: &Params) -> f32 {
+    let biome_albedo = params.biomes[&tile.biome].albedo;
+    let vegetation_cover = (tile.biomass / params.sim.biomass_full_cover).clamp(0.0, 1.0);
+    let albedo =
+        biome_albedo * (1.0 - vegetation_cover) + params.sim.vegetation_albedo * vegetation_cover;
+
+    // Ice and snow cover vegetation
+    let ice_cover = tile.ice_cover(params);
+    let albedo = albedo * (1.0 - ice_cover) + params.sim.ice_albedo * ice_cover;
+
+    // Clouds increase with the relative humidity of the air
+    let humidity = (vapor_pressure / saturation_vapor_pressure(tile.temp)).min(1.0);
+    let cloud_cover = params.sim.max_cloud_cover * humidity;
+    albedo * (1.0 - cloud_cover) + params.sim.cloud_albedo * cloud_cover
+}
+
+/// Calculate transmittance of longwave radiation by gray atmosphere approximation
+fn calc_transmittance(planet: &Planet, params: &Params) -> f32 {
+    let optical_depth: f32 = params
+        .sim
+        .greenhouse_optical_depth
+        .iter()
+        .map(|(kind, factor)| factor * planet.atmo.partial_pressure(*kind, params))
+        .sum();
+    1.0 / (1.0 + 0.75 * optical_depth)
+}
@@ -0,0 +1,204 @@
+use super::*;
+use geom::{CyclicMode, Direction};
+
+/// Density of water [kg/m^3]
+pub const WATER_DENSITY: f32 = 1000.0;
+/// Freezing point of water [K]
+pub const FREEZING_POINT: f32 = 273.15;
+/// Pressure of the triple point of water [atm]
+pub const TRIPLE_POINT_PRESSURE: f32 = 0.006;
+
+pub fn sim_hydrosphere(planet: &mut Planet, sim: &Sim, params: &Params) {
+    // Mass of water per depth on a tile [Mt/m]
+    let mass_per_depth = sim.tile_area * WATER_DENSITY * 1.0E-9;
+    let vapor_pressure = planet.atmo.partial_pressure(GasKind::WaterVapor, params);
+    let liquid_allowed =
+        planet.atmo.total_mass() / params.sim.total_mass_per_atm > TRIPLE_POINT_PRESSURE;
+
+    // Phase changes on the surface
+    let size = planet.map.size();
+    let mut evaporated = 0.0;
+    let mut condensation_demand = Array2d::new(size.0, size.1, 0.0f32);
+    for p in planet.map.iter_idx() {
+        let tile = &mut planet.map[p];
+        let temp = tile.surface_temp;
+
+        if temp < FREEZING_POINT {
+            let d = (params.sim.freezing_rate * (FREEZING_POINT - temp)).min(tile.water);
+            tile.water -= d;
+            tile.ice += d;
+        } else if liquid_allowed {
+            let d = (params.sim.freezing_rate * (temp - FREEZING_POINT)).min(tile.ice);
+            tile.ice -= d;
+            tile.water += d;
+        } else {
+            // Liquid water cannot exist under too low pressure
+            tile.ice += tile.water + tile.soil_moisture;
+            tile.water = 0.0;
+            tile.soil_moisture = 0.0;
+        }
+
+        let saturation = saturation_vapor_pressure(temp);
+        if vapor_pressure < saturation {
+            let mut d = params.sim.evaporation_rate * (saturation - vapor_pressure);
+            let from_water = d.min(tile.water);
+            tile.water -= from_water;
+            d -= from_water;
+            let from_soil = d.min(tile.soil_moisture);
+            tile.soil_moisture -= from_soil;
+            d -= from_soil;
+            let from_ice = d.min(tile.ice);
+            tile.ice -= from_ice;
+            evaporated += from_water + from_soil + from_ice;
+        } else {
+            condensation_demand[p] = params.sim.condensation_rate * (vapor_pressure - saturation);
+        }
+    }
+
+    // Precipitation is limited by the amount of vapor in the atmosphere
+    let vapor = planet.atmo.mass.get_mut(&GasKind::WaterVapor).unwrap();
+    *vapor += evaporated * mass_per_depth;
+    let total_demand: f32 = condensation_demand.iter().sum();
+    let ratio = if total_demand > 0.0 {
+        (*vapor / (total_demand * mass_per_depth)).min(1.0)
+    } else {
+        0.0
+    };
+
+    let mut condensed = 0.0;
+    for p in planet.map.iter_idx() {
+        if condensation_demand[p] <= 0.0 {
+            continue;
+        }
+        let tile = &mut planet.map[p];
+        let d = condensation_demand[p] * ratio;
+        if tile.surface_temp < FREEZING_POINT || !liquid_allowed {
+            tile.ice += d;
+        } else {
+            tile.water += d;
+        }
+        condensed += d;
+    }
+    *vapor = (*vapor - condensed * mass_per_depth).max(0.0);
+
+    // Soil holds water up to its field capacity, and the rest flows
+    for p in planet.map.iter_idx() {
+        let tile = &mut planet.map[p];
+        let d = (params.sim.soil_field_capacity - tile.soil_moisture)
+            .max(0.0)
+            .min(tile.water);
+        tile.water -= d;
+        tile.soil_moisture += d;
+    }
+
+    flow_water(planet, params);
+}
+
+/// Liquid water flows to lower adjacent tiles
+fn flow_water(planet: &mut Planet, params: &Params) {
+    let size = planet.map.size();
+    let mut water_diff = Array2d::new(size.0, size.1, 0.0f32);
+
+    for p in planet.map.iter_idx() {
+        let tile = &planet.map[p];
+        if tile.water <= 0.0 {
+            continue;
+        }
+        let level = tile.height + tile.water;
+
+        let mut outflows = [(Coords(0, 0), 0.0f32); 4];
+        for (i, dir) in Direction::FOUR_DIRS.into_iter().enumerate() {
+            if let Some(adjacent) = CyclicMode::X.convert_coords(size, p + dir.as_coords()) {
+                let adjacent_tile = &planet.map[adjacent];
+                let delta = level - (adjacent_tile.height + adjacent_tile.water);
+                if delta > 0.0 {
+                    outflows[i] = (adjacent, 0.5 * params.sim.water_flow_rate * delta);
+                }
+            }
+        }
+
+        let total: f32 = outflows.iter().map(|(_, v)| v).sum();
+        if total <= 0.0 {
+            continue;
+        }
+        let ratio = (tile.water / total).min(1.0);
+        for (adjacent, v) in outflows {
+            if v > 0.0 {
+                water_diff[adjacent] += v * ratio;
+                water_diff[p] -= v * ratio;
+            }
+        }
+    }
+
+    for p in planet.map.iter_idx() {
+        let water = &mut planet.map[p].water;
+        *water = (*water + water_diff[p]).max(0.0);
+    }
+}
+
+impl Tile {
+    /// The ratio of the tile surface covered by ice
+    pub fn ice_cover(&self, params: &Params) -> f32 {
+        (self.ice / params.sim.ice_full_cover).clamp(0.0, 1.0)
+    }
+}
+
+/// Saturation vapor pressure of water by Tetens equation [atm]
+pub fn saturation_vapor_pressure(temp: f32) -> f32 {
+    let t = temp - FREEZING_POINT;
+    let hpa = if temp >= FREEZING_POINT {
+        6.1078 * (17.27 * t / (temp - 35.85)).exp()
+    } else {
+        6.1078 * (21.875 * t / (temp - 7.65)).exp()
+    };
+    hpa / 1013.25
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn water_is_conserved() {
+        let mut params: Params =
+            ron::from_str(include_str!("../../assets/planet.params.ron")).unwrap();
+        // Make precipitation limited by the amount of vapor
+        params.sim.condensation_rate = 50.0;
+        let start = &params.start;
+        let mut planet = Planet::new(32, 16, start, &start.default_gen_conf);
+        *planet.atmo.mass.get_mut(&GasKind::WaterVapor).unwrap() = 1.0e+7;
+        for p in planet.map.iter_idx() {
+            let tile = &mut planet.map[p];
+            tile.water = (p.0 % 4) as f32;
+            tile.ice = (p.1 % 3) as f32;
+            // Air colder than the surface, and the reverse on the other half
+            tile.surface_temp = 240.0 + 4.0 * p.1 as f32;
+            tile.temp = if p.0 < 16 {
+                tile.surface_temp - 30.0
+            } else {
+                tile.surface_temp + 30.0
+            };
+        }
+        let sim = Sim::new(&planet);
+        let mass_per_depth = sim.tile_area * WATER_DENSITY * 1.0E-9;
+        let total = |planet: &Planet| {
+            let surface: f32 = planet
+                .map
+                .iter()
+                .map(|tile| tile.water + tile.soil_moisture + tile.ice)
+                .sum();
+            surface * mass_per_depth + planet.atmo.mass[&GasKind::WaterVapor]
+        };
+
+        let before = total(&planet);
+        for _ in 0..10 {
+            sim_hydrosphere(&mut planet, &sim, &params);
+        }
+        let after = total(&planet);
+
+        assert!(
+            (after - before).abs() / before < 1.0E-3,
+            "{before} -> {after}"
+        );
+    }
+}
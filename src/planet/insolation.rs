@@ -0,0 +1,124 @@
+use super::*;
+use std::f32::consts::PI;
+
+/// Position of the star seen from the planet
+#[derive(Clone, Copy, Debug)]
+pub struct SunPosition {
+    /// Latitude of the sub-solar point [rad]
+    pub declination: f32,
+    /// Longitude of the sub-solar point [rad]. None if insolation is averaged over a day
+    pub sub_solar_longitude: Option<f32>,
+    /// Solar constant at the current distance from the star [W/m^2]
+    pub solar_constant: f32,
+}
+
+impl PlanetBasics {
+    /// Length of a solar day [days]. None if the planet is tidally locked
+    pub fn solar_day(&self) -> Option<f32> {
+        if (self.rotation_period - self.orbital_period).abs() < 1.0E-3 * self.orbital_period {
+            None
+        } else {
+            Some(1.0 / (1.0 / self.rotation_period - 1.0 / self.orbital_period))
+        }
+    }
+}
+
+impl Planet {
+    /// The number of simulation steps per day
+    pub fn n_steps_per_day(&self, params: &Params) -> u32 {
+        if params.sim.diurnal_cycle {
+            params.sim.n_steps_per_day.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Elapsed time including the current step in the day [days]
+    pub fn time(&self, params: &Params) -> f32 {
+        self.days as f32 + self.step as f32 / self.n_steps_per_day(params) as f32
+    }
+
+    /// Calculate the latitude of the sub-solar point [rad] and the squared ratio of
+    /// the semi-major axis to the current distance from the star
+    pub fn calc_sub_solar_latitude_and_distance(&self, time: f32) -> (f32, f32) {
+        let basics = &self.basics;
+        let e = basics.eccentricity;
+
+        // Solve Kepler's equation by Newton's method
+        let mean_anomaly = 2.0 * PI * (time % basics.orbital_period) / basics.orbital_period;
+        let mut eccentric_anomaly = mean_anomaly;
+        for _ in 0..5 {
+            eccentric_anomaly -= (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+                / (1.0 - e * eccentric_anomaly.cos());
+        }
+
+        // True anomaly, which is measured from the vernal equinox in this model
+        let true_anomaly = 2.0
+            * ((1.0 + e).sqrt() * (eccentric_anomaly / 2.0).sin())
+                .atan2((1.0 - e).sqrt() * (eccentric_anomaly / 2.0).cos());
+        let distance = 1.0 - e * eccentric_anomaly.cos();
+
+        let sub_solar_latitude = (basics.axial_tilt.to_radians().sin() * true_anomaly.sin()).asin();
+        (sub_solar_latitude, 1.0 / (distance * distance))
+    }
+
+    pub fn sun_position(&self, params: &Params) -> SunPosition {
+        let time = self.time(params);
+        let (declination, distance_factor) = self.calc_sub_solar_latitude_and_distance(time);
+
+        // Tidally locked planets always face the star at the center of the map
+        let sub_solar_longitude = match self.basics.solar_day() {
+            None => Some(PI),
+            Some(solar_day) if params.sim.diurnal_cycle => {
+                Some((PI - 2.0 * PI * time / solar_day).rem_euclid(2.0 * PI))
+            }
+            Some(_) => None,
+        };
+
+        SunPosition {
+            declination,
+            sub_solar_longitude,
+            solar_constant: self.basics.solar_constant * distance_factor,
+        }
+    }
+}
+
+impl SunPosition {
+    /// Insolation at the top of the atmosphere [W/m^2]
+    pub fn insolation(&self, longitude: f32, latitude: f32) -> f32 {
+        let declination = self.declination;
+
+        if let Some(sub_solar_longitude) = self.sub_solar_longitude {
+            let hour_angle = longitude - sub_solar_longitude;
+            let cos_zenith = latitude.sin() * declination.sin()
+                + latitude.cos() * declination.cos() * hour_angle.cos();
+            self.solar_constant * cos_zenith.max(0.0)
+        } else {
+            // Hour angle of sunset
+            let h0 = (-latitude.tan() * declination.tan())
+                .clamp(-1.0, 1.0)
+                .acos();
+            self.solar_constant / PI
+                * (h0 * latitude.sin() * declination.sin()
+                    + latitude.cos() * declination.cos() * h0.sin())
+        }
+    }
+}
+
+/// Calculate insolation at the top of the atmosphere of tiles
+pub fn calc_insolation(planet: &Planet, sim: &mut Sim, params: &Params) {
+    let sun_position = planet.sun_position(params);
+    let n_steps = planet.n_steps_per_day(params) as f32;
+
+    for p in planet.map.iter_idx() {
+        let (longitude, latitude) = planet.calc_longitude_latitude(p);
+        sim.insolation[p] = sun_position.insolation(longitude, latitude)
+            * sim.insolation_factor[p.1 as usize].max(0.0)
+            * (1.0 - sim.star_dimming).max(0.0);
+
+        if planet.step == 0 {
+            sim.daily_insolation[p] = 0.0;
+        }
+        sim.daily_insolation[p] += sim.insolation[p] / n_steps;
+    }
+}
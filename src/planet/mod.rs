@@ -1,13 +1,29 @@
 mod action;
 mod atmo;
+mod biomass;
+mod biome;
 mod buildings;
+mod carbon;
 mod defs;
+mod event_card;
+mod gas;
+mod gen;
+mod geometry;
+mod habitability;
 mod heat_transfer;
+mod hydro;
+mod insolation;
+mod natural_event;
 mod resources;
 mod sim;
+mod wind;
 
 pub use self::atmo::Atmosphere;
+pub use self::buildings::BuildingKind;
 pub use self::defs::*;
+pub use self::event_card::*;
+pub use self::insolation::SunPosition;
+pub use self::natural_event::{NaturalEvent, NaturalEvents};
 pub use self::resources::*;
 pub use self::sim::Sim;
 use fnv::FnvHashMap;
@@ -21,14 +37,31 @@ use strum::IntoEnumIterator;
 pub struct Tile {
     pub biome: Biome,
     pub structure: Structure,
+    /// Priority of the structure overriding the priority of its kind
+    pub structure_priority: Option<Priority>,
     pub height: f32,
+    /// Biomass density [kg/m^2]
     pub biomass: f32,
+    /// Air temperature [K]
     pub temp: f32,
+    /// Surface temperature [K]
+    pub surface_temp: f32,
+    /// Depth of liquid water [m]
+    pub water: f32,
+    /// Water held in soil, which does not flow [m]
+    pub soil_moisture: f32,
+    /// Depth of ice [m]
+    pub ice: f32,
+    /// CO2 dissolved in water [kg/m^2]
+    pub dissolved_co2: f32,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Player {
     pub buildable_structures: BTreeSet<StructureKind>,
+    /// Priority of each kind of structures
+    #[serde(default)]
+    pub structure_priority: FnvHashMap<StructureKind, Priority>,
 }
 
 impl Default for Tile {
@@ -36,9 +69,15 @@ impl Default for Tile {
         Self {
             biome: Biome::Rock,
             structure: Structure::None,
+            structure_priority: None,
             height: 0.0,
             biomass: 0.0,
             temp: 300.0,
+            surface_temp: 300.0,
+            water: 0.0,
+            soil_moisture: 0.0,
+            ice: 0.0,
+            dissolved_co2: 0.0,
         }
     }
 }
@@ -47,11 +86,17 @@ impl Default for Tile {
 pub struct Building {
     pub n: u32,
     pub enabled: u32,
+    pub priority: Priority,
+    /// Whether buildings with the effect dimming the star use it
+    #[serde(default)]
+    pub dim_star: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Planet {
     pub days: u64,
+    /// Simulation step in the current day
+    pub step: u32,
     pub basics: PlanetBasics,
     pub player: Player,
     pub res: Resources,
@@ -59,14 +104,17 @@ pub struct Planet {
     pub atmo: Atmosphere,
     pub orbit: FnvHashMap<OrbitalBuildingKind, Building>,
     pub star_system: FnvHashMap<StarSystemBuildingKind, Building>,
+    pub natural_events: NaturalEvents,
+    pub event_cards: EventCardState,
 }
 
 impl Planet {
-    pub fn new(w: u32, h: u32, start_params: &StartParams) -> Planet {
+    pub fn new(w: u32, h: u32, start_params: &StartParams, gen_conf: &GenConf) -> Planet {
         let map = Array2d::new(w, h, Tile::default());
 
         let mut planet = Planet {
             days: 0,
+            step: 0,
             basics: start_params.basics.clone(),
             player: Player::default(),
             res: Resources::new(start_params),
@@ -78,6 +126,8 @@ impl Planet {
             star_system: StarSystemBuildingKind::iter()
                 .map(|kind| (kind, Building::default()))
                 .collect(),
+            natural_events: NaturalEvents::new(gen_conf.seed),
+            event_cards: EventCardState::default(),
         };
 
         for (kind, &n) in &start_params.orbital_buildings {
@@ -91,6 +141,8 @@ impl Planet {
             building.enabled = n;
         }
 
+        self::gen::generate(&mut planet, gen_conf);
+
         planet
             .player
             .buildable_structures
@@ -103,17 +155,40 @@ impl Planet {
             .player
             .buildable_structures
             .insert(StructureKind::Heater);
+        planet
+            .player
+            .buildable_structures
+            .insert(StructureKind::Warehouse);
 
         planet
     }
 
     pub fn advance(&mut self, sim: &mut Sim, params: &Params) {
-        self.days += 1;
+        if self.step == 0 {
+            self.days += 1;
+            self::buildings::advance(self, sim, params);
+        }
 
-        self::buildings::advance(self, params);
         self::heat_transfer::advance(self, sim, params);
 
+        // Stages below are simulated once per day
+        self.step += 1;
+        if self.step < self.n_steps_per_day(params) {
+            return;
+        }
+        self.step = 0;
+
+        self::natural_event::sim_natural_events(self, sim, params);
+        self::hydro::sim_hydrosphere(self, sim, params);
+        self::biomass::sim_biomass(self, sim, params);
+        self::carbon::sim_carbon_cycle(self, sim, params);
+        self::biome::sim_biome_transition(self, params);
+
+        self::gas::sim_gas_field(self, sim, params);
         atmo::sim_atmosphere(self, params);
+        self::habitability::calc_habitability(self, sim, params);
+
+        self::event_card::check_event_cards(self, sim, params);
     }
 
     pub fn calc_longitude_latitude<T: Into<Coords>>(&self, coords: T) -> (f32, f32) {
@@ -0,0 +1,131 @@
+use super::gen::splitmix64;
+use super::geometry::tiles_in_radius;
+use super::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum NaturalEvent {
+    MeteorImpact {
+        p: Coords,
+        /// The number of destroyed structures
+        destroyed: u32,
+    },
+    SolarFlare {
+        /// Lost energy
+        energy_loss: f32,
+    },
+    VolcanicEruption {
+        p: Coords,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NaturalEvents {
+    /// State of the random number generator
+    rng: u64,
+    /// Occurred events and the days
+    pub history: Vec<(u64, NaturalEvent)>,
+}
+
+impl NaturalEvents {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: seed,
+            history: Vec::new(),
+        }
+    }
+
+    /// Generate a random number in 0.0 ~ 1.0
+    fn gen_f32(&mut self) -> f32 {
+        self.rng = self.rng.wrapping_add(0x9E3779B97F4A7C15);
+        (splitmix64(self.rng) >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Generate a random number in min ~ max
+    fn gen_range(&mut self, min: u32, max: u32) -> u32 {
+        let n = max.saturating_sub(min) + 1;
+        min + ((self.gen_f32() * n as f32) as u32).min(n - 1)
+    }
+
+    fn gen_coords(&mut self, size: (u32, u32)) -> Coords {
+        Coords(
+            self.gen_range(0, size.0 - 1) as i32,
+            self.gen_range(0, size.1 - 1) as i32,
+        )
+    }
+}
+
+pub fn sim_natural_events(planet: &mut Planet, sim: &Sim, params: &Params) {
+    let event_params = &params.natural_events;
+    let size = planet.map.size();
+
+    if planet.natural_events.gen_f32() < event_params.meteor_impact.probability {
+        let ep = &event_params.meteor_impact;
+        let p = planet.natural_events.gen_coords(size);
+        let r = planet
+            .natural_events
+            .gen_range(ep.crater_radius.0, ep.crater_radius.1);
+        let mut destroyed = 0;
+
+        for (q, d) in tiles_in_radius(size, p, r) {
+            planet.map[q].height -= ep.crater_depth * (1.0 - d / (r as f32 + 1.0));
+            if destroy_structure(planet, q, params) {
+                destroyed += 1;
+            }
+        }
+        planet.atmo.dust += ep.dust;
+
+        planet
+            .natural_events
+            .history
+            .push((planet.days, NaturalEvent::MeteorImpact { p, destroyed }));
+    }
+
+    if planet.natural_events.gen_f32() < event_params.solar_flare.probability {
+        let energy = planet.res.get_stock_mut(ResourceKind::Energy);
+        let energy_loss = *energy * event_params.solar_flare.energy_loss;
+        *energy -= energy_loss;
+
+        planet
+            .natural_events
+            .history
+            .push((planet.days, NaturalEvent::SolarFlare { energy_loss }));
+    }
+
+    if planet.natural_events.gen_f32() < event_params.volcanic_eruption.probability {
+        let ep = &event_params.volcanic_eruption;
+        let p = planet.natural_events.gen_coords(size);
+        let tiles = tiles_in_radius(size, p, ep.radius);
+
+        // Heat is given to the surface around the volcano equally
+        let heat = ep.heat / tiles.len() as f32;
+        for (q, _) in tiles {
+            planet.map[q].surface_temp += heat / sim.surface_heat_cap[q];
+        }
+        *planet.atmo.mass.get_mut(&GasKind::CarbonDioxide).unwrap() += ep.co2;
+        planet.atmo.dust += ep.dust;
+
+        planet
+            .natural_events
+            .history
+            .push((planet.days, NaturalEvent::VolcanicEruption { p }));
+    }
+}
+
+/// Destroy the structure on the tile. Returns true if a structure is destroyed
+fn destroy_structure(planet: &mut Planet, p: Coords, params: &Params) -> bool {
+    let p = if let Structure::Occupied { by } = planet.map[p].structure {
+        by
+    } else {
+        p
+    };
+    let Some(attrs) = params.structures.get(&planet.map[p].structure.kind()) else {
+        return false;
+    };
+
+    planet.map[p].structure = Structure::None;
+    planet.map[p].structure_priority = None;
+    for p_rel in attrs.size.occupied_tiles().into_iter() {
+        planet.map[p + p_rel].structure = Structure::None;
+    }
+    true
+}
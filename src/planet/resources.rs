@@ -1,6 +1,9 @@
 use super::*;
 use serde::{Deserialize, Serialize};
 
+/// Capacity of the resources not listed in `StartParams::resource_cap`
+const DEFAULT_RESOURCE_CAP: f32 = 1.0E+06;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Resources {
     pub stock: ResourceMap,
@@ -8,19 +11,13 @@ pub struct Resources {
     pub diff: ResourceMap,
 }
 
-impl Default for Resources {
-    fn default() -> Self {
-        Resources {
-            stock: ResourceKind::iter().map(|kind| (kind, 0.0)).collect(),
-            cap: ResourceKind::iter().map(|kind| (kind, 1.0E+06)).collect(),
-            diff: ResourceKind::iter().map(|kind| (kind, 0.0)).collect(),
-        }
-    }
-}
-
 impl Resources {
     pub fn new(start_params: &StartParams) -> Self {
-        let mut res = Resources::default();
+        let mut res = Resources {
+            stock: ResourceKind::iter().map(|kind| (kind, 0.0)).collect(),
+            cap: Self::base_cap(start_params),
+            diff: ResourceKind::iter().map(|kind| (kind, 0.0)).collect(),
+        };
 
         for (kind, v) in &start_params.resources {
             *res.get_stock_mut(*kind) += v;
@@ -29,6 +26,20 @@ impl Resources {
         res
     }
 
+    /// Capacity of resources without storage buildings
+    pub fn base_cap(start_params: &StartParams) -> ResourceMap {
+        ResourceKind::iter()
+            .map(|kind| {
+                let cap = start_params
+                    .resource_cap
+                    .get(&kind)
+                    .copied()
+                    .unwrap_or(DEFAULT_RESOURCE_CAP);
+                (kind, cap)
+            })
+            .collect()
+    }
+
     pub fn get_stock_mut(&mut self, kind: ResourceKind) -> &mut f32 {
         self.stock.get_mut(&kind).unwrap()
     }
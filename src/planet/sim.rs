@@ -7,29 +7,109 @@ pub struct Sim {
     pub n_tile: u32,
     /// Tile area [m^2]
     pub tile_area: f32,
+    /// Conductance to east and west tiles of each row
+    pub ew_conductance: Vec<f32>,
+    /// Conductance to north tiles of each row
+    pub ns_conductance: Vec<f32>,
+    /// Conductance across the south pole
+    pub south_pole_conductance: f32,
+    /// Maximum sum of conductance to adjacent tiles
+    pub max_conductance_sum: f32,
     /// Atmosphere temprature
     pub atemp: Array2d<f32>,
     /// Atmosphere temprature (used for calculation)
     pub atemp_new: Array2d<f32>,
+    /// Surface temprature
+    pub stemp: Array2d<f32>,
     /// Atmosphere heat capacity [J/K]
     pub atmo_heat_cap: Array2d<f32>,
+    /// Surface heat capacity [J/K]
+    pub surface_heat_cap: Array2d<f32>,
+    /// Insolation at the top of the atmosphere [W/m^2]
+    pub insolation: Array2d<f32>,
+    /// Daily mean insolation at the top of the atmosphere [W/m^2]
+    pub daily_insolation: Array2d<f32>,
     /// Tile albedo
     pub albedo: Array2d<f32>,
+    /// Wind velocity of tiles (eastward, northward) [m/s]
+    pub wind: Array2d<(f32, f32)>,
+    /// Gas mass of tiles [Mt]
+    pub gas: FnvHashMap<GasKind, Array2d<f32>>,
+    /// The number of sub-steps needed in the last heat transfer calculation
+    pub n_substeps: u32,
+    /// Air transport was weakened because it needed more sub-steps than the limit
+    pub transport_limited: bool,
+    /// Ratio of insolation changed by buildings of each row
+    pub insolation_factor: Vec<f32>,
+    /// Ratio of starlight collected by buildings
+    pub star_dimming: f32,
+    /// The number of buildings stopped by the lack of resources for upkeep
+    pub stopped_buildings: FnvHashMap<BuildingKind, u32>,
+    /// Structures stopped by the lack of resources for upkeep
+    pub stopped_structures: Array2d<bool>,
+    /// Heat supplied to tiles by buildings [W]
+    pub heat_supply: Array2d<f32>,
+    /// Tile fertility boosted by structures
+    pub fertility: Array2d<f32>,
+    /// Tile habitability for humans
+    pub habitability: Array2d<f32>,
 }
 
 impl Sim {
     pub fn new(planet: &Planet) -> Self {
         let size = planet.map.size();
+        // All tiles have the same area in the equal-area projection
         let tile_area = 4.0 * PI * planet.basics.radius * planet.basics.radius
             / (size.0 as f32 * size.1 as f32);
 
+        let (ew_conductance, ns_conductance) = self::geometry::calc_conductance(planet);
+        let south_pole_conductance =
+            self::geometry::pole_conductance(planet.calc_longitude_latitude((0, 0)).1, size.0);
+        let max_conductance_sum = (0..size.1 as usize)
+            .map(|y| {
+                let south = if y > 0 {
+                    ns_conductance[y - 1]
+                } else {
+                    south_pole_conductance
+                };
+                2.0 * ew_conductance[y] + ns_conductance[y] + south
+            })
+            .fold(0.0, f32::max);
+
         Sim {
             n_tile: size.0 * size.1,
             tile_area,
+            ew_conductance,
+            ns_conductance,
+            south_pole_conductance,
+            max_conductance_sum,
             atemp: Array2d::new(size.0, size.1, 0.0),
             atemp_new: Array2d::new(size.0, size.1, 0.0),
+            stemp: Array2d::new(size.0, size.1, 0.0),
             atmo_heat_cap: Array2d::new(size.0, size.1, 0.0),
+            surface_heat_cap: Array2d::new(size.0, size.1, 0.0),
+            insolation: Array2d::new(size.0, size.1, 0.0),
+            daily_insolation: Array2d::new(size.0, size.1, 0.0),
             albedo: Array2d::new(size.0, size.1, 0.0),
+            wind: Array2d::new(size.0, size.1, (0.0, 0.0)),
+            gas: planet
+                .atmo
+                .mass
+                .iter()
+                .map(|(&kind, &mass)| {
+                    let v = mass / (size.0 * size.1) as f32;
+                    (kind, Array2d::new(size.0, size.1, v))
+                })
+                .collect(),
+            n_substeps: 1,
+            transport_limited: false,
+            stopped_buildings: FnvHashMap::default(),
+            stopped_structures: Array2d::new(size.0, size.1, false),
+            insolation_factor: vec![1.0; size.1 as usize],
+            star_dimming: 0.0,
+            heat_supply: Array2d::new(size.0, size.1, 0.0),
+            fertility: Array2d::new(size.0, size.1, 0.0),
+            habitability: Array2d::new(size.0, size.1, 0.0),
         }
     }
 }
@@ -0,0 +1,83 @@
+use super::geometry::{adjacent_tile, tile_size};
+use super::*;
+
+pub const MAX_COURANT_NUMBER: f32 = 0.5;
+
+/// Calculate wind of tiles from temperature gradient and Coriolis effect
+pub fn calc_wind(planet: &Planet, sim: &mut Sim, params: &Params) {
+    let size = planet.map.size();
+    let angular_velocity = 2.0 * PI / (planet.basics.rotation_period * params.sim.secs_per_day);
+    let friction = params.sim.wind_friction;
+
+    for p in planet.map.iter_idx() {
+        let latitude = planet.calc_longitude_latitude(p).1;
+        let (dx, dy) = tile_size(planet, latitude);
+
+        // Temperature gradient, air is accelerated toward warmer and lower pressure tiles
+        let temp = |d: Coords| sim.atemp[adjacent_tile(size, p, d)];
+        let grad_x = (temp(Coords(1, 0)) - temp(Coords(-1, 0))) / (2.0 * dx);
+        let grad_y = (temp(Coords(0, 1)) - temp(Coords(0, -1))) / (2.0 * dy);
+        let fx = params.sim.wind_pressure_factor * grad_x;
+        let fy = params.sim.wind_pressure_factor * grad_y;
+
+        // Steady state under the pressure force, friction and Coriolis force
+        let coriolis = 2.0 * angular_velocity * latitude.sin();
+        let d = friction * friction + coriolis * coriolis;
+        let u = (friction * fx + coriolis * fy) / d;
+        let v = (friction * fy - coriolis * fx) / d;
+
+        // Zonal bands of trade winds, westerlies and polar easterlies
+        let u = u - params.sim.wind_zonal_speed * (6.0 * latitude.abs()).sin();
+
+        let speed = (u * u + v * v).sqrt();
+        let ratio = if speed > params.sim.wind_max_speed {
+            params.sim.wind_max_speed / speed
+        } else {
+            1.0
+        };
+        sim.wind[p] = (u * ratio, v * ratio);
+    }
+}
+
+/// Calculate the change of a quantity advected by wind in the given seconds.
+/// The changes are adjusted so that the total amount is conserved.
+pub fn calc_advection(
+    planet: &Planet,
+    wind: &Array2d<(f32, f32)>,
+    values: &Array2d<f32>,
+    delta: &mut Array2d<f32>,
+    secs: f32,
+) {
+    let size = planet.map.size();
+
+    for p in planet.map.iter_idx() {
+        let (u, v) = wind[p];
+        let (dx, dy) = tile_size(planet, planet.calc_longitude_latitude(p).1);
+
+        // Courant numbers are clamped to keep the upwind scheme stable
+        let cx = (u.abs() * secs / dx).min(MAX_COURANT_NUMBER);
+        let cy = (v.abs() * secs / dy).min(MAX_COURANT_NUMBER);
+
+        let upwind_x = adjacent_tile(size, p, Coords(if u > 0.0 { -1 } else { 1 }, 0));
+        let upwind_y = adjacent_tile(size, p, Coords(0, if v > 0.0 { -1 } else { 1 }));
+
+        delta[p] = cx * (values[upwind_x] - values[p]) + cy * (values[upwind_y] - values[p]);
+    }
+
+    // Tiles have the same area, so the mean change is the loss or gain by the scheme
+    let mean = delta.iter().sum::<f32>() / (size.0 * size.1) as f32;
+    for p in planet.map.iter_idx() {
+        delta[p] -= mean;
+    }
+}
+
+/// Maximum Courant number of wind on the map in the given seconds
+pub fn max_courant_number(planet: &Planet, sim: &Sim, secs: f32) -> f32 {
+    let mut max = 0.0f32;
+    for p in planet.map.iter_idx() {
+        let (u, v) = sim.wind[p];
+        let (dx, dy) = tile_size(planet, planet.calc_longitude_latitude(p).1);
+        max = max.max(u.abs() * secs / dx).max(v.abs() * secs / dy);
+    }
+    max
+}
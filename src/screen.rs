@@ -23,6 +23,7 @@ pub enum CursorMode {
     Normal,
     Demolition,
     Build(StructureKind),
+    SetPriority(Option<Priority>),
     EditBiome(Biome),
 }
 
@@ -315,6 +316,40 @@ fn update_hover_tile(
             .id();
         color_entities.push(id);
     }
+
+    // Highlight tiles affected by the structure
+    let (CursorMode::Build(kind), Some(hover_p)) = (&*cursor_mode, hover_tile.0 .0) else {
+        return;
+    };
+    let attrs = &params.structures[kind];
+    if attrs.range == 0 {
+        return;
+    }
+    let planet_w = planet.map.size().0 as i32;
+    for (q, w) in attrs.effect_area(planet.map.size(), hover_p) {
+        // Tiles over the edge of the map are drawn next to the hovered tile
+        let dx = (q.0 - hover_p.0 + planet_w / 2).rem_euclid(planet_w) - planet_w / 2;
+        let dy = q.1 - hover_p.1;
+
+        let mut transform = Transform { ..default() };
+        transform.translation.x = (tile_i + dx) as f32 * TILE_SIZE + TILE_SIZE / 2.0;
+        transform.translation.y = (tile_j + dy) as f32 * TILE_SIZE + TILE_SIZE / 2.0;
+        transform.translation.z = 910.0;
+
+        let id = commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(1.0, 1.0, 1.0, 0.5 * w),
+                    ..default()
+                },
+                texture: ui_textures.get(UiTexture::TileColored),
+                visibility: Visibility { is_visible: true },
+                transform,
+                ..default()
+            })
+            .id();
+        color_entities.push(id);
+    }
 }
 
 #[derive(Clone, Default, Debug, Resource)]
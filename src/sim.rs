@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::draw::UpdateMap;
 use crate::screen::Centering;
+use crate::text::Unit;
 use crate::{planet::*, GameSpeed, GameState};
 
 #[derive(Clone, Copy, Debug)]
@@ -9,7 +10,7 @@ pub struct SimPlugin;
 
 #[derive(Clone, Debug)]
 pub enum ManagePlanet {
-    New(u32, u32),
+    New(u32, u32, GenConf),
     Save(String),
     Load(String),
 }
@@ -31,12 +32,25 @@ impl Plugin for SimPlugin {
     }
 }
 
-fn start_sim(mut commands: Commands, mut update_map: ResMut<UpdateMap>, params: Res<Params>) {
+fn start_sim(
+    mut commands: Commands,
+    mut update_map: ResMut<UpdateMap>,
+    params: Res<Params>,
+    planet: Option<Res<Planet>>,
+) {
+    // Planet may be already created by ManagePlanet
+    if planet.is_some() {
+        update_map.update();
+        return;
+    }
+
     let planet = Planet::new(
         params.start.default_size.0,
         params.start.default_size.1,
         &params.start,
+        &params.start.default_gen_conf,
     );
+    commands.insert_resource(Sim::new(&planet));
     commands.insert_resource(planet);
     update_map.update();
 }
@@ -75,7 +89,25 @@ fn update(
     }
     *last_update = Some(*count_frame);
     update_map.update();
+
+    let n_events = planet.natural_events.history.len();
     planet.advance(&mut sim, &params);
+    for (_, event) in &planet.natural_events.history[n_events..] {
+        push_natural_event_msg(event);
+    }
+}
+
+fn push_natural_event_msg(event: &NaturalEvent) {
+    let s = match event {
+        NaturalEvent::MeteorImpact { p, destroyed } => {
+            t!("meteor_impact"; x=p.0, y=p.1, destroyed=destroyed)
+        }
+        NaturalEvent::SolarFlare { energy_loss } => {
+            t!("solar_flare"; energy_loss=ResourceKind::Energy.display_with_value(*energy_loss))
+        }
+        NaturalEvent::VolcanicEruption { p } => t!("volcanic_eruption"; x=p.0, y=p.1),
+    };
+    crate::msg::push_msg(crate::msg::MsgKind::Notice, s);
 }
 
 fn manage_planet(
@@ -92,8 +124,8 @@ fn manage_planet(
 
     for e in er_manage_planet.iter() {
         let new_planet = match e {
-            ManagePlanet::New(w, h) => {
-                let planet = Planet::new(*w, *h, &params.start);
+            ManagePlanet::New(w, h, gen_conf) => {
+                let planet = Planet::new(*w, *h, &params.start, gen_conf);
                 Some(planet)
             }
             ManagePlanet::Save(path) => {
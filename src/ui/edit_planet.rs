@@ -22,6 +22,7 @@ pub fn edit_planet_window(
     mut cursor_mode: ResMut<CursorMode>,
     mut wos: ResMut<WindowsOpenState>,
     conf: Res<Conf>,
+    params: Res<Params>,
     mut ew_manage_planet: EventWriter<ManagePlanet>,
     mut current_panel: Local<Panel>,
     mut map_panel: Local<MapPanel>,
@@ -42,7 +43,7 @@ pub fn edit_planet_window(
             ui.separator();
 
             match *current_panel {
-                Panel::Map => map_panel.ui(ui, &mut ew_manage_planet, &mut cursor_mode),
+                Panel::Map => map_panel.ui(ui, &mut ew_manage_planet, &mut cursor_mode, &params),
                 Panel::Planet => planet_ui(ui, &mut planet),
                 Panel::Atmosphere => atmo_ui(ui, &mut planet),
             }
@@ -59,6 +60,7 @@ pub fn edit_planet_window(
 pub struct MapPanel {
     new_w: u32,
     new_h: u32,
+    gen_conf: Option<GenConf>,
     biome: Biome,
 }
 
@@ -68,14 +70,24 @@ impl MapPanel {
         ui: &mut egui::Ui,
         ew_manage_planet: &mut EventWriter<ManagePlanet>,
         cursor_mode: &mut CursorMode,
+        params: &Params,
     ) {
+        let gen_conf = self
+            .gen_conf
+            .get_or_insert_with(|| params.start.default_gen_conf.clone());
+
         ui.add(egui::Slider::new(&mut self.new_w, 2..=100).text("width"));
+        ui.add(egui::Slider::new(&mut self.new_h, 2..=100).text("height"));
         ui.horizontal(|ui| {
-            ui.add(egui::Slider::new(&mut self.new_h, 2..=100).text("height"));
-            if ui.button("New").clicked() {
-                ew_manage_planet.send(ManagePlanet::New(self.new_w, self.new_h));
-            }
+            ui.add(egui::DragValue::new(&mut gen_conf.seed));
+            ui.label("seed");
         });
+        ui.add(egui::Slider::new(&mut gen_conf.sea_level, -5000.0..=5000.0).text("sea level"));
+        ui.add(egui::Slider::new(&mut gen_conf.roughness, 0.0..=1.0).text("roughness"));
+        ui.add(egui::Slider::new(&mut gen_conf.n_continents, 0..=20).text("continents"));
+        if ui.button("New").clicked() {
+            ew_manage_planet.send(ManagePlanet::New(self.new_w, self.new_h, gen_conf.clone()));
+        }
 
         ui.horizontal(|ui| {
             egui::ComboBox::from_id_source(Biome::Ocean)
@@ -94,10 +106,25 @@ impl MapPanel {
 }
 
 fn planet_ui(ui: &mut egui::Ui, planet: &mut Planet) {
+    ui.add(egui::Slider::new(&mut planet.basics.density, 500.0..=10000.0).text(t!("density")));
     ui.add(
         egui::Slider::new(&mut planet.basics.solar_constant, 0.0..=3000.0)
             .text(t!("solar-constant")),
     );
+    ui.add(egui::Slider::new(&mut planet.basics.axial_tilt, 0.0..=90.0).text(t!("axial-tilt")));
+    ui.add(
+        egui::Slider::new(&mut planet.basics.orbital_period, 10.0..=3600.0)
+            .text(t!("orbital-period")),
+    );
+    ui.add(egui::Slider::new(&mut planet.basics.eccentricity, 0.0..=0.9).text(t!("eccentricity")));
+    ui.add(
+        egui::Slider::new(&mut planet.basics.rotation_period, 0.1..=3600.0)
+            .text(t!("rotation-period"))
+            .logarithmic(true),
+    );
+    if ui.button(t!("tidally-locked")).clicked() {
+        planet.basics.rotation_period = planet.basics.orbital_period;
+    }
 }
 
 fn atmo_ui(ui: &mut egui::Ui, planet: &mut Planet) {
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+use super::{convert_rect, OccupiedScreenSpace};
+use crate::conf::Conf;
+use crate::planet::*;
+
+pub fn event_card_window(
+    mut egui_ctx: ResMut<EguiContext>,
+    mut occupied_screen_space: ResMut<OccupiedScreenSpace>,
+    mut planet: ResMut<Planet>,
+    conf: Res<Conf>,
+    params: Res<Params>,
+) {
+    let Some(card) = planet.pending_event_card(&params) else {
+        return;
+    };
+
+    let mut chosen = None;
+    let rect = egui::Window::new(t!(&card.title))
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(egui_ctx.ctx_mut(), |ui| {
+            ui.label(t!(&card.text));
+            ui.separator();
+            for (i, choice) in card.choices.iter().enumerate() {
+                if ui.button(t!(&choice.text)).clicked() {
+                    chosen = Some(i);
+                }
+            }
+            if card.choices.is_empty() && ui.button(t!("close")).clicked() {
+                chosen = Some(0);
+            }
+        })
+        .unwrap()
+        .response
+        .rect;
+    occupied_screen_space
+        .window_rects
+        .push(convert_rect(rect, conf.scale_factor));
+
+    if let Some(i) = chosen {
+        planet.choose_event_card(i, &params);
+    }
+}
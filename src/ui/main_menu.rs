@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext};
 
 use crate::conf::{Conf, ConfChange};
-use crate::planet::Params;
+use crate::planet::{GenConf, Params};
 use crate::sim::ManagePlanet;
 use crate::text::Lang;
 use strum::IntoEnumIterator;
@@ -13,6 +13,7 @@ pub fn main_menu(
     params: Res<Params>,
     mut conf: ResMut<Conf>,
     mut ew_conf_change: EventWriter<ConfChange>,
+    mut seed: Local<u64>,
 ) {
     egui::Window::new(t!("menu"))
         .title_bar(false)
@@ -21,10 +22,18 @@ pub fn main_menu(
         .resizable(false)
         .show(egui_ctx.ctx_mut(), |ui| {
             ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-                if ui.button(t!("new")).clicked() {
-                    let size = params.start.default_size;
-                    ew_manage_planet.send(ManagePlanet::New(size.0, size.1));
-                }
+                ui.horizontal(|ui| {
+                    if ui.button(t!("new")).clicked() {
+                        let size = params.start.default_size;
+                        let gen_conf = GenConf {
+                            seed: *seed,
+                            ..params.start.default_gen_conf.clone()
+                        };
+                        ew_manage_planet.send(ManagePlanet::New(size.0, size.1, gen_conf));
+                    }
+                    ui.label(t!("seed"));
+                    ui.add(egui::DragValue::new(&mut *seed));
+                });
                 if ui.button(t!("load")).clicked() {
                     ew_manage_planet.send(ManagePlanet::Load("test.planet".into()));
                 }
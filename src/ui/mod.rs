@@ -1,4 +1,5 @@
 mod edit_planet;
+mod event_card;
 mod main_menu;
 mod orbit;
 mod star_system;
@@ -78,7 +79,8 @@ impl Plugin for UiPlugin {
                     .with_system(stat::stat_window.label("ui_windows"))
                     .with_system(msg_window.label("ui_windows"))
                     .with_system(game_menu_window.label("ui_windows"))
-                    .with_system(edit_planet::edit_planet_window.label("ui_windows")),
+                    .with_system(edit_planet::edit_planet_window.label("ui_windows"))
+                    .with_system(event_card::event_card_window.label("ui_windows")),
             )
             .add_system(exit_on_esc);
     }
@@ -166,6 +168,8 @@ fn panels(
     mut wos: ResMut<WindowsOpenState>,
     mut speed: ResMut<GameSpeed>,
     planet: Res<Planet>,
+    sim: Res<Sim>,
+    params: Res<Params>,
     textures: Res<EguiTextures>,
     conf: Res<Conf>,
 ) {
@@ -174,7 +178,14 @@ fn panels(
     occupied_screen_space.occupied_left = egui::SidePanel::left("left_panel")
         .resizable(true)
         .show(egui_ctx.ctx_mut(), |ui| {
-            sidebar(ui, &cursor_mode, &planet, hover_tile.get_single().unwrap());
+            sidebar(
+                ui,
+                &cursor_mode,
+                &planet,
+                &sim,
+                &params,
+                hover_tile.get_single().unwrap(),
+            );
             ui.allocate_rect(ui.available_rect_before_wrap(), egui::Sense::hover());
         })
         .response
@@ -196,15 +207,23 @@ fn panels(
         * conf.scale_factor;
 }
 
-fn sidebar(ui: &mut egui::Ui, cursor_mode: &CursorMode, planet: &Planet, hover_tile: &HoverTile) {
+fn sidebar(
+    ui: &mut egui::Ui,
+    cursor_mode: &CursorMode,
+    planet: &Planet,
+    sim: &Sim,
+    params: &Params,
+    hover_tile: &HoverTile,
+) {
     let mut stock: Vec<_> = planet.res.stock.iter().collect();
     stock.sort_by_key(|&(res, _)| res);
     for (kind, v) in stock.into_iter() {
         ui.horizontal(|ui| {
             ui.label(&format!(
-                "{}: {}",
+                "{}: {} / {}",
                 t!(kind.as_ref()),
-                kind.display_with_value(*v)
+                kind.display_with_value(*v),
+                kind.display_with_value(planet.res.cap[kind]),
             ));
             let diff = planet.res.diff[kind];
             let sign = if diff > 0.0 { '+' } else { '-' };
@@ -229,6 +248,12 @@ fn sidebar(ui: &mut egui::Ui, cursor_mode: &CursorMode, planet: &Planet, hover_t
         CursorMode::Demolition => {
             ui.label(t!("demolition"));
         }
+        CursorMode::SetPriority(priority) => {
+            let priority = priority
+                .as_ref()
+                .map_or("priority-of-kind", |priority| priority.as_ref());
+            ui.label(format!("{}: {}", t!("priority"), t!(priority)));
+        }
         CursorMode::EditBiome(biome) => {
             ui.label(format!("biome editing: {}", biome.as_ref()));
         }
@@ -256,6 +281,68 @@ fn sidebar(ui: &mut egui::Ui, cursor_mode: &CursorMode, planet: &Planet, hover_t
             tile.temp - 273.15
         ));
 
+        ui.label(format!(
+            "{}: {:.1} °C",
+            t!("surface-temprature"),
+            tile.surface_temp - 273.15
+        ));
+
+        ui.label(format!(
+            "{}: {:.2} m, {}: {:.2} m",
+            t!("water"),
+            tile.water,
+            t!("ice"),
+            tile.ice
+        ));
+
+        ui.label(format!(
+            "{}: {:.3} m",
+            t!("soil-moisture"),
+            tile.soil_moisture
+        ));
+
+        let (u, v) = sim.wind[p];
+        ui.label(format!(
+            "{}: {:.1} m/s {}",
+            t!("wind"),
+            (u * u + v * v).sqrt(),
+            wind_arrow(u, v)
+        ));
+
+        if params.sim.per_tile_gas {
+            for kind in GasKind::iter() {
+                ui.label(format!(
+                    "{}: {:.2e} atm",
+                    t!(kind.as_ref()),
+                    sim.local_partial_pressure(kind, p, params)
+                ));
+            }
+        }
+
+        ui.label(format!("{}: {:.2} kg/m2", t!("biomass"), tile.biomass));
+
+        let structure_p = if let Structure::Occupied { by } = tile.structure {
+            by
+        } else {
+            p
+        };
+        let structure_tile = &planet.map[structure_p];
+        if params
+            .structures
+            .contains_key(&structure_tile.structure.kind())
+        {
+            ui.separator();
+            ui.label(t!(structure_tile.structure.kind().as_ref()));
+            ui.label(format!(
+                "{}: {}",
+                t!("priority"),
+                t!(planet.structure_priority(structure_p).as_ref())
+            ));
+            if sim.stopped_structures[structure_p] {
+                ui.label(egui::RichText::new(t!("stopped")).color(egui::Color32::RED));
+            }
+        }
+
         let s = match &tile.structure {
             Structure::None => None,
             Structure::Occupied { by } => {
@@ -272,6 +359,14 @@ fn sidebar(ui: &mut egui::Ui, cursor_mode: &CursorMode, planet: &Planet, hover_t
     };
 }
 
+/// Arrow showing the direction the wind blows toward
+fn wind_arrow(u: f32, v: f32) -> &'static str {
+    const ARROWS: [&str; 8] = ["→", "↗", "↑", "↖", "←", "↙", "↓", "↘"];
+    let angle = v.atan2(u).rem_euclid(2.0 * std::f32::consts::PI);
+    let i = (angle / (std::f32::consts::PI / 4.0)).round() as usize % 8;
+    ARROWS[i]
+}
+
 fn toolbar(
     ui: &mut egui::Ui,
     _cursor_mode: &mut CursorMode,
@@ -394,7 +489,7 @@ fn build_window(
     mut wos: ResMut<WindowsOpenState>,
     mut cursor_mode: ResMut<CursorMode>,
     conf: Res<Conf>,
-    planet: Res<Planet>,
+    mut planet: ResMut<Planet>,
     params: Res<Params>,
 ) {
     if !wos.build {
@@ -408,17 +503,34 @@ fn build_window(
             if ui.button(t!("demolition")).clicked() {
                 *cursor_mode = CursorMode::Demolition;
             }
+            ui.horizontal(|ui| {
+                ui.label(t!("priority"));
+                for priority in Priority::iter() {
+                    if ui.button(t!(priority.as_ref())).clicked() {
+                        *cursor_mode = CursorMode::SetPriority(Some(priority));
+                    }
+                }
+                if ui.button(t!("priority-of-kind")).clicked() {
+                    *cursor_mode = CursorMode::SetPriority(None);
+                }
+            });
             ui.separator();
-            for kind in &planet.player.buildable_structures {
-                let s: &str = kind.as_ref();
-                if ui
-                    .button(t!(s))
-                    .on_hover_ui(build_button_tooltip(*kind, &params))
-                    .clicked()
-                {
-                    *cursor_mode = CursorMode::Build(*kind);
+            let planet = &mut *planet;
+            egui::Grid::new("table_build").show(ui, |ui| {
+                for kind in &planet.player.buildable_structures {
+                    let s: &str = kind.as_ref();
+                    if ui
+                        .button(t!(s))
+                        .on_hover_ui(build_button_tooltip(*kind, &params))
+                        .clicked()
+                    {
+                        *cursor_mode = CursorMode::Build(*kind);
+                    }
+                    let priority = planet.player.structure_priority.entry(*kind).or_default();
+                    priority_combo_box(ui, s, priority);
+                    ui.end_row();
                 }
-            }
+            });
         })
         .unwrap()
         .response
@@ -428,6 +540,16 @@ fn build_window(
         .push(convert_rect(rect, conf.scale_factor));
 }
 
+fn priority_combo_box(ui: &mut egui::Ui, id: &str, priority: &mut Priority) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(t!(priority.as_ref()))
+        .show_ui(ui, |ui| {
+            for p in Priority::iter() {
+                ui.selectable_value(priority, p, t!(p.as_ref()));
+            }
+        });
+}
+
 fn build_button_tooltip(kind: StructureKind, params: &Params) -> impl FnOnce(&mut Ui) + '_ {
     building_desc_tooltip(&params.structures[&kind].building)
 }
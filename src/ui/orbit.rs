@@ -2,7 +2,9 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext};
 use strum::IntoEnumIterator;
 
-use super::{building_desc_tooltip, convert_rect, OccupiedScreenSpace, WindowsOpenState};
+use super::{
+    building_desc_tooltip, convert_rect, priority_combo_box, OccupiedScreenSpace, WindowsOpenState,
+};
 use crate::conf::Conf;
 use crate::planet::*;
 
@@ -11,6 +13,7 @@ pub fn orbit_window(
     mut occupied_screen_space: ResMut<OccupiedScreenSpace>,
     mut wos: ResMut<WindowsOpenState>,
     mut planet: ResMut<Planet>,
+    sim: Res<Sim>,
     conf: Res<Conf>,
     params: Res<Params>,
 ) {
@@ -28,6 +31,8 @@ pub fn orbit_window(
                     ui.label("");
                     ui.label("");
                     ui.label(t!("enabled"));
+                    ui.label(t!("priority"));
+                    ui.label("");
                     ui.label("");
                     ui.end_row();
                     for kind in OrbitalBuildingKind::iter() {
@@ -36,6 +41,7 @@ pub fn orbit_window(
                         ui.label(t!(kind.as_ref()));
                         ui.label(format!("{}", building.n));
                         ui.add(egui::Slider::new(&mut building.enabled, 0..=building.n));
+                        priority_combo_box(ui, kind.as_ref(), &mut building.priority);
                         if ui
                             .add_enabled(buildable, egui::Button::new(t!("add")))
                             .on_hover_ui(building_desc_tooltip(&params.orbital_buildings[&kind]))
@@ -46,6 +52,19 @@ pub fn orbit_window(
                         {
                             planet.build_orbital_building(kind, &params);
                         }
+                        let stopped = sim
+                            .stopped_buildings
+                            .get(&BuildingKind::Orbital(kind))
+                            .copied()
+                            .unwrap_or(0);
+                        if stopped > 0 {
+                            ui.label(
+                                egui::RichText::new(format!("{}: {}", t!("stopped"), stopped))
+                                    .color(egui::Color32::RED),
+                            );
+                        } else {
+                            ui.label("");
+                        }
                         ui.end_row();
                     }
                 });
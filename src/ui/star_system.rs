@@ -2,7 +2,9 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext};
 use strum::IntoEnumIterator;
 
-use super::{building_desc_tooltip, convert_rect, OccupiedScreenSpace, WindowsOpenState};
+use super::{
+    building_desc_tooltip, convert_rect, priority_combo_box, OccupiedScreenSpace, WindowsOpenState,
+};
 use crate::conf::Conf;
 use crate::planet::*;
 
@@ -11,6 +13,7 @@ pub fn star_system_window(
     mut occupied_screen_space: ResMut<OccupiedScreenSpace>,
     mut wos: ResMut<WindowsOpenState>,
     mut planet: ResMut<Planet>,
+    sim: Res<Sim>,
     conf: Res<Conf>,
     params: Res<Params>,
 ) {
@@ -28,6 +31,9 @@ pub fn star_system_window(
                     ui.label("");
                     ui.label("");
                     ui.label(t!("enabled"));
+                    ui.label(t!("priority"));
+                    ui.label(t!("dim-star"));
+                    ui.label("");
                     ui.label("");
                     ui.end_row();
                     for kind in StarSystemBuildingKind::iter() {
@@ -36,6 +42,15 @@ pub fn star_system_window(
                         ui.label(t!(kind.as_ref()));
                         ui.label(format!("{}", building.n));
                         ui.add(egui::Slider::new(&mut building.enabled, 0..=building.n));
+                        priority_combo_box(ui, kind.as_ref(), &mut building.priority);
+                        if matches!(
+                            params.star_system_buildings[&kind].effect,
+                            Some(BuildingEffect::DimStar { .. })
+                        ) {
+                            ui.checkbox(&mut building.dim_star, "");
+                        } else {
+                            ui.label("");
+                        }
                         if ui
                             .add_enabled(buildable, egui::Button::new(t!("add")))
                             .on_hover_ui(building_desc_tooltip(
@@ -48,6 +63,19 @@ pub fn star_system_window(
                         {
                             planet.build_star_system_building(kind, &params);
                         }
+                        let stopped = sim
+                            .stopped_buildings
+                            .get(&BuildingKind::StarSystem(kind))
+                            .copied()
+                            .unwrap_or(0);
+                        if stopped > 0 {
+                            ui.label(
+                                egui::RichText::new(format!("{}: {}", t!("stopped"), stopped))
+                                    .color(egui::Color32::RED),
+                            );
+                        } else {
+                            ui.label("");
+                        }
                         ui.end_row();
                     }
                 });
@@ -12,6 +12,7 @@ pub enum Panel {
     #[default]
     Planet,
     Atmosphere,
+    Habitability,
 }
 
 pub fn stat_window(
@@ -20,6 +21,8 @@ pub fn stat_window(
     mut wos: ResMut<WindowsOpenState>,
     conf: Res<Conf>,
     planet: Res<Planet>,
+    sim: Res<Sim>,
+    params: Res<Params>,
     mut current_panel: Local<Panel>,
 ) {
     if !wos.stat {
@@ -39,7 +42,8 @@ pub fn stat_window(
 
             match *current_panel {
                 Panel::Planet => planet_stat(ui, &planet),
-                Panel::Atmosphere => atmo_stat(ui, &planet),
+                Panel::Atmosphere => atmo_stat(ui, &planet, &sim),
+                Panel::Habitability => habitability_stat(ui, &planet, &sim, &params),
             }
         })
         .unwrap()
@@ -60,12 +64,45 @@ fn planet_stat(ui: &mut egui::Ui, planet: &Planet) {
             ui.label(t!("density"));
             ui.label(format!("{:.1} g/cm3", planet.basics.density / 1000.0));
             ui.end_row();
+            ui.label(t!("surface-gravity"));
+            ui.label(format!("{:.2} m/s2", planet.basics.surface_gravity()));
+            ui.end_row();
+            ui.label(t!("escape-velocity"));
+            ui.label(format!(
+                "{:.1} km/s",
+                planet.basics.escape_velocity() / 1000.0
+            ));
+            ui.end_row();
             ui.label(t!("solar-constant"));
             ui.label(format!("{:.0} W/m2", planet.basics.solar_constant));
+            ui.end_row();
+            ui.label(t!("axial-tilt"));
+            ui.label(format!("{:.1}°", planet.basics.axial_tilt));
+            ui.end_row();
+            ui.label(t!("orbital-period"));
+            ui.label(format!(
+                "{:.0} {}",
+                planet.basics.orbital_period,
+                t!("days")
+            ));
+            ui.end_row();
+            ui.label(t!("eccentricity"));
+            ui.label(format!("{:.3}", planet.basics.eccentricity));
+            ui.end_row();
+            ui.label(t!("rotation-period"));
+            if planet.basics.solar_day().is_some() {
+                ui.label(format!(
+                    "{:.2} {}",
+                    planet.basics.rotation_period,
+                    t!("days")
+                ));
+            } else {
+                ui.label(t!("tidally-locked"));
+            }
         });
 }
 
-fn atmo_stat(ui: &mut egui::Ui, planet: &Planet) {
+fn atmo_stat(ui: &mut egui::Ui, planet: &Planet, sim: &Sim) {
     ui.label(format!(
         "{}: {:.2} atm",
         t!("atmosphere-pressure"),
@@ -85,4 +122,35 @@ fn atmo_stat(ui: &mut egui::Ui, planet: &Planet) {
             ui.end_row();
         }
     });
+    ui.separator();
+
+    ui.label(format!(
+        "{}: {}",
+        t!("heat-transfer-substeps"),
+        sim.n_substeps
+    ));
+    if sim.transport_limited {
+        ui.label(egui::RichText::new(t!("transport-limited")).color(egui::Color32::RED));
+    }
+}
+
+fn habitability_stat(ui: &mut egui::Ui, planet: &Planet, sim: &Sim, params: &Params) {
+    let habitable_tiles = sim.habitability.iter().filter(|h| **h >= 0.5).count();
+
+    egui::Grid::new("table_habitability")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label(t!("earth-similarity-index"));
+            ui.label(format!("{:.3}", planet.earth_similarity_index(params)));
+            ui.end_row();
+            ui.label(t!("habitability"));
+            ui.label(format!("{:.1}%", sim.mean_habitability() * 100.0));
+            ui.end_row();
+            ui.label(t!("habitable-area"));
+            ui.label(format!(
+                "{:.1}%",
+                habitable_tiles as f32 / sim.n_tile as f32 * 100.0
+            ));
+            ui.end_row();
+        });
 }